@@ -0,0 +1,99 @@
+use std::time::Duration;
+use crate::budget::Limits;
+
+pub(crate) enum Command {
+    Solve,
+    Repl,
+    Compare,
+    /// Validates the input without solving it.
+    Check,
+    /// Runs a registered command once, with its words.
+    Execute(Vec<String>),
+}
+
+/// Command line of a day binary:
+/// `[repl | compare | check [<file>] | <command>...] [--input <file>] [--param <key>=<value>]... [--max-steps <n>] [--timeout <duration>]`
+///
+/// `--<key> <value>` is a shorthand for `--param <key>=<value>`.
+pub(crate) struct Args {
+    pub command: Command,
+    pub input: Option<String>,
+    pub params: Vec<(String, String)>,
+    pub limits: Limits,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse(args: impl Iterator<Item=String>) -> Result<Self, String> {
+        let mut args = args.peekable();
+        let mut parsed = Self {
+            command: Command::Solve,
+            input: None,
+            params: Vec::new(),
+            limits: Limits::default(),
+            help: false,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--input" => {
+                    let path = args.next().ok_or("--input expects a file")?;
+                    parsed.input = Some(path);
+                }
+                "--param" => {
+                    let param = args.next().ok_or("--param expects <key>=<value>")?;
+                    let (key, value) = param.split_once('=')
+                        .ok_or_else(|| format!("malformed parameter `{}`, expected <key>=<value>", param))?;
+                    parsed.params.push((key.to_string(), value.to_string()));
+                }
+                "--max-steps" => {
+                    let steps = args.next().ok_or("--max-steps expects a number of steps")?;
+                    let steps = steps.parse()
+                        .map_err(|e| format!("invalid number of steps `{}`: {}", steps, e))?;
+                    parsed.limits.max_steps = Some(steps);
+                }
+                "--timeout" => {
+                    let duration = args.next().ok_or("--timeout expects a duration")?;
+                    parsed.limits.timeout = Some(parse_duration(&duration)?);
+                }
+                "repl" => parsed.command = Command::Repl,
+                "compare" => parsed.command = Command::Compare,
+                "check" => {
+                    parsed.command = Command::Check;
+                    if let Some(path) = args.next_if(|arg| !arg.starts_with('-')) {
+                        parsed.input = Some(path);
+                    }
+                }
+                _ if !arg.starts_with('-') => match &mut parsed.command {
+                    Command::Execute(words) => words.push(arg),
+                    _ => parsed.command = Command::Execute(vec![arg]),
+                },
+                _ if arg.starts_with("--") => {
+                    let key = &arg[2..];
+                    let value = args.next().ok_or_else(|| format!("{} expects a value", arg))?;
+                    parsed.params.push((key.to_string(), value));
+                }
+                _ => return Err(format!("unexpected argument `{}`", arg)),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Parses `500ms`, `10s` or `2m`, a bare number being seconds.
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let digits = duration.find(|c: char| !c.is_ascii_digit()).unwrap_or(duration.len());
+    let (amount, unit) = duration.split_at(digits);
+
+    let amount = amount.parse::<u64>()
+        .map_err(|_| format!("invalid duration `{}`, expected e.g. 500ms, 10s or 2m", duration))?;
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(amount)),
+        "" | "s" => Ok(Duration::from_secs(amount)),
+        "m" => Ok(Duration::from_secs(amount * 60)),
+        _ => Err(format!("invalid duration `{}`, expected e.g. 500ms, 10s or 2m", duration)),
+    }
+}
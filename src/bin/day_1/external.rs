@@ -0,0 +1,236 @@
+//! Distance and similarity of location lists too large for memory.
+//!
+//! Each chosen column is read in runs of at most `run_size` ids, sorted and written to a
+//! temporary file. The runs are then merged as streams, pairwise for the distance and as a
+//! merge-join of equal ids for the similarity, so that memory stays bounded by the run size.
+//! Too many runs to keep open at once are first merged in passes into fewer, longer runs.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{self, AtomicUsize};
+use advent_of_code_2024::{checked, Context};
+use crate::Columns;
+
+/// The most runs of a column merged at once, bounding the files open at the same time.
+const MERGE_WIDTH: usize = 64;
+
+/// Sorted ids in a temporary file, removed once dropped.
+struct Run {
+    path: PathBuf,
+}
+
+impl Run {
+    fn create() -> io::Result<(Self, BufWriter<File>)> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let run = Run {
+            path: env::temp_dir().join(format!(
+                "day_1-{}-{}.run",
+                process::id(),
+                NEXT.fetch_add(1, atomic::Ordering::Relaxed),
+            )),
+        };
+        let writer = BufWriter::new(File::create(&run.path)?);
+
+        Ok((run, writer))
+    }
+
+    fn write(ids: &mut Vec<usize>) -> io::Result<Self> {
+        let (run, mut writer) = Run::create()?;
+
+        ids.sort_unstable();
+        for id in ids.drain(..) {
+            writer.write_all(&(id as u64).to_le_bytes())?;
+        }
+        writer.flush()?;
+
+        Ok(run)
+    }
+
+    /// A single run of the ids of `runs`.
+    fn merge(runs: &[Run], context: &Context) -> io::Result<Self> {
+        let (run, mut writer) = Run::create()?;
+
+        let mut merge = Merge::new(runs)?;
+        while let Some(id) = merge.next()? {
+            context.step();
+            writer.write_all(&(id as u64).to_le_bytes())?;
+        }
+        writer.flush()?;
+
+        Ok(run)
+    }
+}
+
+/// Merges the runs [`MERGE_WIDTH`] at a time, pass after pass, until they can all be merged at
+/// once.
+fn narrow(mut runs: Vec<Run>, context: &Context) -> io::Result<Vec<Run>> {
+    while runs.len() > MERGE_WIDTH {
+        runs = runs.chunks(MERGE_WIDTH)
+            .map(|chunk| Run::merge(chunk, context))
+            .collect::<io::Result<_>>()?;
+    }
+
+    Ok(runs)
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn read_id(reader: &mut impl Read) -> io::Result<Option<usize>> {
+    let mut bytes = [0; 8];
+    match reader.read_exact(&mut bytes) {
+        Ok(()) => Ok(Some(u64::from_le_bytes(bytes) as usize)),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The ids of several runs, in order.
+struct Merge {
+    readers: Vec<BufReader<File>>,
+    heap: BinaryHeap<Reverse<(usize, usize)>>,
+}
+
+impl Merge {
+    fn new(runs: &[Run]) -> io::Result<Self> {
+        let mut merge = Merge {
+            readers: Vec::with_capacity(runs.len()),
+            heap: BinaryHeap::with_capacity(runs.len()),
+        };
+
+        for (index, run) in runs.iter().enumerate() {
+            let mut reader = BufReader::new(File::open(&run.path)?);
+            if let Some(id) = read_id(&mut reader)? {
+                merge.heap.push(Reverse((id, index)));
+            }
+            merge.readers.push(reader);
+        }
+
+        Ok(merge)
+    }
+
+    fn next(&mut self) -> io::Result<Option<usize>> {
+        let Some(Reverse((id, index))) = self.heap.pop() else {
+            return Ok(None);
+        };
+
+        if let Some(next) = read_id(&mut self.readers[index])? {
+            self.heap.push(Reverse((next, index)));
+        }
+
+        Ok(Some(id))
+    }
+
+    /// The next id with the number of times it occurs.
+    fn next_group(&mut self) -> io::Result<Option<(usize, usize)>> {
+        let Some(id) = self.next()? else {
+            return Ok(None);
+        };
+
+        let mut count = 1;
+        while let Some(&Reverse((next, _))) = self.heap.peek() {
+            if next != id {
+                break;
+            }
+            self.next()?;
+            count += 1;
+        }
+
+        Ok(Some((id, count)))
+    }
+}
+
+/// Reads the two chosen columns of the file into sorted runs.
+fn sort_runs(path: &str, Columns(left, right): Columns, run_size: usize) -> io::Result<(Vec<Run>, Vec<Run>)> {
+    let (mut left_runs, mut right_runs) = (Vec::new(), Vec::new());
+    let (mut left_ids, mut right_ids) = (Vec::with_capacity(run_size), Vec::with_capacity(run_size));
+
+    for (y, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let column = |column: usize| {
+            let id = line.split_whitespace().nth(column - 1)
+                .ok_or_else(|| format!("line {}: no column {}", y + 1, column))?;
+            id.parse::<usize>()
+                .map_err(|_| format!("line {}: `{}` is not a location id", y + 1, id))
+        };
+        left_ids.push(column(left).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?);
+        right_ids.push(column(right).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?);
+
+        if left_ids.len() == run_size {
+            left_runs.push(Run::write(&mut left_ids)?);
+            right_runs.push(Run::write(&mut right_ids)?);
+        }
+    }
+
+    if !left_ids.is_empty() {
+        left_runs.push(Run::write(&mut left_ids)?);
+        right_runs.push(Run::write(&mut right_ids)?);
+    }
+
+    Ok((left_runs, right_runs))
+}
+
+fn distance(left_runs: &[Run], right_runs: &[Run], context: &Context) -> io::Result<usize> {
+    let (mut left, mut right) = (Merge::new(left_runs)?, Merge::new(right_runs)?);
+    let mut distance = 0;
+
+    while let (Some(left), Some(right)) = (left.next()?, right.next()?) {
+        context.step();
+        distance = checked::add(distance, left.abs_diff(right));
+    }
+
+    Ok(distance)
+}
+
+fn similarity(left_runs: &[Run], right_runs: &[Run], context: &Context) -> io::Result<usize> {
+    let (mut left, mut right) = (Merge::new(left_runs)?, Merge::new(right_runs)?);
+    let (mut left_group, mut right_group) = (left.next_group()?, right.next_group()?);
+    let mut similarity = 0;
+
+    while let (Some((left_id, left_count)), Some((right_id, right_count))) = (left_group, right_group) {
+        context.step();
+
+        match left_id.cmp(&right_id) {
+            Ordering::Less => left_group = left.next_group()?,
+            Ordering::Greater => right_group = right.next_group()?,
+            Ordering::Equal => {
+                let contribution = checked::mul(checked::mul(left_id, left_count), right_count);
+                similarity = checked::add(similarity, contribution);
+                left_group = left.next_group()?;
+                right_group = right.next_group()?;
+            }
+        }
+    }
+
+    Ok(similarity)
+}
+
+/// Distance and similarity of the columns of `path` chosen by the `columns` parameter, without
+/// loading them.
+pub fn external(path: &str, run_size: usize, context: &Context) -> Result<String, String> {
+    let solve = || {
+        let (left_runs, right_runs) = sort_runs(path, context.param("columns"), run_size)?;
+        let (left_runs, right_runs) = (narrow(left_runs, context)?, narrow(right_runs, context)?);
+
+        Ok::<_, io::Error>(format!(
+            "Distance: {}\nSimilarity: {}",
+            distance(&left_runs, &right_runs, context)?,
+            similarity(&left_runs, &right_runs, context)?,
+        ))
+    };
+
+    solve().map_err(|e| format!("{}: {}", path, e))
+}
@@ -1,41 +1,222 @@
+use std::cmp::Reverse;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
+use serde_json::json;
 
-fn main() {
-    let input = include_str!("input.txt");
+mod external;
 
-    let (mut left, mut right): (Vec<_>, Vec<_>) = input
-        .lines()
-        .map(|line| line.split_once("   ").unwrap())
-        .map(|(left, right)| (
-            left.parse::<usize>().unwrap(),
-            right.parse::<usize>().unwrap(),
-        ))
-        .unzip();
+/// The two lists to compare, as 1-based column numbers like `1,3`.
+#[derive(Copy, Clone)]
+struct Columns(usize, usize);
 
-    left.sort();
-    right.sort();
+impl FromStr for Columns {
+    type Err = String;
 
-    let distance: usize = left.iter()
-        .zip(right.iter())
-        .map(|(left, right)| left.abs_diff(*right))
-        .sum();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let column = |column: &str| column.trim().parse::<usize>().ok().filter(|&column| column > 0);
+
+        s.split_once(',')
+            .and_then(|(left, right)| Some(Columns(column(left)?, column(right)?)))
+            .ok_or_else(|| "expected two column numbers like 1,2".to_string())
+    }
+}
+
+impl Display for Columns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.0, self.1)
+    }
+}
+
+fn validate(input: &str) -> Vec<String> {
+    let Some(width) = input.lines().find(|line| !line.trim().is_empty()).map(|line| line.split_whitespace().count()) else {
+        return vec!["the input is empty, expected lists of location ids".to_string()];
+    };
+
+    let mut problems = Vec::new();
+    if width < 2 {
+        problems.push("a single column found, expected at least two lists".to_string());
+    }
+
+    problems.extend(validate::lines(input, 1, |line| {
+        let ids = line.split_whitespace().collect::<Vec<_>>();
+
+        if let Some(id) = ids.iter().find(|id| id.parse::<usize>().is_err()) {
+            Err(format!("`{}` is not a location id", id))
+        } else if !ids.is_empty() && ids.len() != width {
+            Err(format!("{} columns, expected {} like the first line", ids.len(), width))
+        } else {
+            Ok(())
+        }
+    }));
+
+    problems
+}
+
+/// Every column of the input, blank lines skipped.
+fn parse(input: &str) -> Vec<Vec<usize>> {
+    let mut columns: Vec<Vec<usize>> = Vec::new();
+
+    for line in input.lines() {
+        for (column, id) in line.split_whitespace().enumerate() {
+            if column == columns.len() {
+                columns.push(Vec::new());
+            }
+            columns[column].push(id.parse().unwrap());
+        }
+    }
+
+    columns
+}
+
+/// The two columns chosen by the `columns` parameter, sorted.
+fn lists(columns: &[Vec<usize>], context: &Context) -> (Vec<usize>, Vec<usize>) {
+    let Columns(left, right) = context.param("columns");
 
-    println!("Distance: {}", distance);
+    let list = |column: usize| {
+        let mut list = columns.get(column - 1)
+            .unwrap_or_else(|| context.fail(format!("no column {}, the input has {}", column, columns.len())))
+            .clone();
+        list.sort();
+        list
+    };
 
-    let left = left.into_iter()
-        .into_grouping_map_by(|&id| id)
+    (list(left), list(right))
+}
+
+fn distance(columns: &Vec<Vec<usize>>, context: &Context) -> usize {
+    let (left, right) = lists(columns, context);
+
+    checked::sum(left.iter()
+        .zip(right.iter())
+        .map(|(left, right)| left.abs_diff(*right)))
+}
+
+/// Similarity of each id of the left list: `(id, left_count, right_count, similarity)`.
+fn contributions(left: &[usize], right: &[usize]) -> Vec<(usize, usize, usize, usize)> {
+    let left = left.iter()
+        .into_grouping_map_by(|&&id| id)
         .fold(0, |count, _, _| count + 1);
 
-    let right = right.into_iter()
-        .into_grouping_map_by(|&id| id)
+    let right = right.iter()
+        .into_grouping_map_by(|&&id| id)
         .fold(0, |count, _, _| count + 1);
 
-    let similarity: usize = left.into_iter()
+    left.into_iter()
         .map(|(id, left_count)| {
-            let right_count = right.get(&id).unwrap_or(&0);
-            id * left_count * right_count
+            let right_count = *right.get(&id).unwrap_or(&0);
+            (id, left_count, right_count, checked::mul(checked::mul(id, left_count), right_count))
         })
-        .sum();
+        .collect()
+}
+
+fn similarity(columns: &Vec<Vec<usize>>, context: &Context) -> usize {
+    let (left, right) = lists(columns, context);
+
+    checked::sum(contributions(&left, &right).into_iter()
+        .map(|(_, _, _, similarity)| similarity))
+}
+
+/// The `top` largest distances, a histogram of all of them and the `top` biggest similarity
+/// contributors, as a table or as JSON.
+fn report(columns: &[Vec<usize>], context: &Context, top: usize, json: bool) -> String {
+    const BUCKETS: usize = 10;
+    const BAR_WIDTH: usize = 40;
+
+    let (left, right) = lists(columns, context);
+
+    let distances = left.iter()
+        .zip(right.iter())
+        .enumerate()
+        .map(|(index, (&left, &right))| (index, left, right, left.abs_diff(right)))
+        .collect::<Vec<_>>();
+
+    let largest = distances.iter()
+        .sorted_by_key(|&&(index, _, _, distance)| (Reverse(distance), index))
+        .take(top)
+        .collect::<Vec<_>>();
+
+    let max_distance = distances.iter().map(|&(_, _, _, distance)| distance).max().unwrap_or(0);
+    let bucket_width = max_distance / BUCKETS + 1;
+    let mut histogram = [0; BUCKETS];
+    for &(_, _, _, distance) in &distances {
+        histogram[distance / bucket_width] += 1;
+    }
 
-    println!("Similarity: {}", similarity);
+    let contributors = contributions(&left, &right).into_iter()
+        .filter(|&(_, _, _, similarity)| similarity > 0)
+        .sorted_by_key(|&(id, _, _, similarity)| (Reverse(similarity), id))
+        .take(top)
+        .collect::<Vec<_>>();
+
+    if json {
+        return json!({
+            "distances": largest.iter().map(|&&(index, left, right, distance)| json!({
+                "index": index,
+                "left": left,
+                "right": right,
+                "distance": distance,
+            })).collect::<Vec<_>>(),
+            "histogram": histogram.iter().enumerate().map(|(bucket, &count)| json!({
+                "from": bucket * bucket_width,
+                "to": (bucket + 1) * bucket_width - 1,
+                "count": count,
+            })).collect::<Vec<_>>(),
+            "similarity": contributors.iter().map(|&(id, left_count, right_count, similarity)| json!({
+                "id": id,
+                "left_count": left_count,
+                "right_count": right_count,
+                "similarity": similarity,
+            })).collect::<Vec<_>>(),
+        }).to_string();
+    }
+
+    let mut report = vec![format!("Largest distances:\n{:>8}  {:>8}  {:>8}  {:>8}", "index", "left", "right", "distance")];
+    report.extend(largest.iter().map(|&&(index, left, right, distance)|
+        format!("{:>8}  {:>8}  {:>8}  {:>8}", index, left, right, distance)));
+
+    let most = histogram.iter().copied().max().unwrap_or(0).max(1);
+    report.push("\nDistance histogram:".to_string());
+    report.extend(histogram.iter().enumerate().map(|(bucket, &count)| format!(
+        "{:>8} - {:<8}  {:<BAR_WIDTH$}  {}",
+        bucket * bucket_width,
+        (bucket + 1) * bucket_width - 1,
+        "#".repeat(count * BAR_WIDTH / most),
+        count,
+    )));
+
+    report.push(format!("\nBiggest similarity contributors:\n{:>8}  {:>8}  {:>8}  {:>10}", "id", "left", "right", "similarity"));
+    report.extend(contributors.iter().map(|&(id, left_count, right_count, similarity)|
+        format!("{:>8}  {:>8}  {:>8}  {:>10}", id, left_count, right_count, similarity)));
+
+    report.join("\n")
+}
+
+fn main() {
+    Day::new("day_1", include_str!("input.txt"), validate, parse)
+        .param("columns", Columns(1, 2))
+        .part("Distance", distance)
+        .part("Similarity", similarity)
+        .command("report", "report [<n>] [json]: the n largest distances (10 by default), their histogram and the biggest similarity contributors", |columns, context, args| {
+            let mut top = 10;
+            let mut json = false;
+            for arg in args {
+                match *arg {
+                    "json" => json = true,
+                    n => top = n.parse().map_err(|_| "usage: report [<n>] [json]".to_string())?,
+                }
+            }
+            Ok(report(columns, context, top, json))
+        })
+        .command("external", "external <file> [<run size>]: distance and similarity of lists too large for memory, sorted in runs of 1000000 ids by default", |_, context, args| match args {
+            [path] => external::external(path, 1_000_000, context),
+            [path, run_size] => match run_size.parse() {
+                Ok(run_size) if run_size > 0 => external::external(path, run_size, context),
+                _ => Err(format!("invalid run size `{}`", run_size)),
+            },
+            _ => Err("usage: external <file> [<run size>]".to_string()),
+        })
+        .run();
 }
@@ -1,4 +1,6 @@
 use std::collections::HashSet;
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 
 trait Grid<T> {
     fn at(&self, pos: (isize, isize)) -> Option<T>;
@@ -59,7 +61,7 @@ fn explore(grid: &Vec<Vec<u8>>, start: (isize, isize), distinct: bool) -> usize
 
         let height = grid.at(pos).unwrap();
         if height == 9 {
-            trails += 1;
+            trails = checked::add(trails, 1);
             continue;
         }
 
@@ -77,28 +79,41 @@ fn explore(grid: &Vec<Vec<u8>>, start: (isize, isize), distinct: bool) -> usize
     trails
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::grid(input);
+    problems.extend(validate::cells(input, "a height", |c| c.is_ascii_digit()));
+    problems
+}
 
-    let grid = input.lines()
+fn parse(input: &str) -> Vec<Vec<u8>> {
+    input.lines()
         .map(|line| line.chars()
             .map(|digit| digit.to_digit(10).unwrap() as u8)
             .collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
 
-    let trailheads = grid.grid_iter()
+fn trailheads(grid: &Vec<Vec<u8>>) -> Vec<(isize, isize)> {
+    grid.grid_iter()
         .filter(|&(cell, _)| cell == 0)
-        .collect::<Vec<_>>();
-
-    let score: usize = trailheads.iter()
-        .map(|(_, position)| explore(&grid, *position, false))
-        .sum();
+        .map(|(_, position)| position)
+        .collect::<Vec<_>>()
+}
 
-    println!("Score: {}", score);
+fn score(grid: &Vec<Vec<u8>>, _: &Context) -> usize {
+    checked::sum(trailheads(grid).iter()
+        .map(|position| explore(grid, *position, false)))
+}
 
-    let rating: usize = trailheads.iter()
-        .map(|(_, position)| explore(&grid, *position, true))
-        .sum();
+fn rating(grid: &Vec<Vec<u8>>, _: &Context) -> usize {
+    checked::sum(trailheads(grid).iter()
+        .map(|position| explore(grid, *position, true)))
+}
 
-    println!("Rating: {}", rating);
+fn main() {
+    Day::new("day_10", include_str!("input.txt"), validate, parse)
+        .part("Score", score)
+        .part("Rating", rating)
+        .show_grid("the topographic map", |grid| grid)
+        .run();
 }
@@ -1,22 +1,25 @@
 use std::collections::HashMap;
+use advent_of_code_2024::checked::{self, Wide};
+use advent_of_code_2024::validate;
+use advent_of_code_2024::Day;
 
-fn number_of_digits(n: usize) -> u32 {
+fn number_of_digits(n: Wide) -> u32 {
     n.checked_ilog10().unwrap_or(0) + 1
 }
 
-fn split_in_half(n: usize) -> (usize, usize) {
+fn split_in_half(n: Wide) -> (Wide, Wide) {
     let half = number_of_digits(n) / 2;
-    let left = n / 10_usize.pow(half);
-    let right = n % 10_usize.pow(half);
+    let left = n / checked::pow(10, half);
+    let right = n % checked::pow(10, half);
     (left, right)
 }
 
 fn expand(
-    stone: usize,
+    stone: Wide,
     depth: usize,
     max_depth: usize,
-    cache: &mut HashMap<usize, HashMap<usize, usize>>,
-) -> usize {
+    cache: &mut HashMap<Wide, HashMap<usize, Wide>>,
+) -> Wide {
     if depth == max_depth {
         return 1;
     }
@@ -28,34 +31,60 @@ fn expand(
         expand(1, depth + 1, max_depth, cache)
     } else if number_of_digits(stone) % 2 == 0 {
         let (left, right) = split_in_half(stone);
-        expand(left, depth + 1, max_depth, cache) +
-            expand(right, depth + 1, max_depth, cache)
+        checked::add(
+            expand(left, depth + 1, max_depth, cache),
+            expand(right, depth + 1, max_depth, cache),
+        )
     } else {
-        expand(stone * 2024, depth + 1, max_depth, cache)
+        expand(checked::mul(stone, 2024), depth + 1, max_depth, cache)
     };
 
     cache.entry(stone).or_default().insert(depth, count);
     count
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-
-    let stones = input.split_whitespace()
-        .map(|x| x.parse::<usize>().unwrap())
-        .collect::<Vec<_>>();
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected stones".to_string()];
+    }
 
-    let mut cache = HashMap::new();
-    let count: usize = stones.iter()
-        .map(|&stone| expand(stone, 0, 25, &mut cache))
-        .sum();
+    validate::lines(input, 1, |stones| {
+        match stones.split_whitespace().find(|stone| stone.parse::<Wide>().is_err()) {
+            Some(stone) => Err(format!("`{}` is not a stone", stone)),
+            None => Ok(()),
+        }
+    })
+}
 
-    println!("Part 1: {}", count);
+fn parse(input: &str) -> Vec<Wide> {
+    input.split_whitespace()
+        .map(|x| x.parse::<Wide>().unwrap())
+        .collect::<Vec<_>>()
+}
 
+fn count(stones: &[Wide], blinks: usize) -> Wide {
     let mut cache = HashMap::new();
-    let count: usize = stones.iter()
-        .map(|&stone| expand(stone, 0, 75, &mut cache))
-        .sum();
+    checked::sum(stones.iter()
+        .map(|&stone| expand(stone, 0, blinks, &mut cache)))
+}
 
-    println!("Part 2: {}", count);
+fn main() {
+    Day::new("day_11", include_str!("input.txt"), validate, parse)
+        .param("part_1_blinks", 25)
+        .param("part_2_blinks", 75)
+        .part("Part 1", |stones, context| {
+            count(stones, context.param("part_1_blinks"))
+        })
+        .part("Part 2", |stones, context| {
+            count(stones, context.param("part_2_blinks"))
+        })
+        .command("blink", "blink <n>: number of stones after n blinks", |stones, _, args| {
+            match args {
+                [blinks] => blinks.parse()
+                    .map(|blinks| count(stones, blinks))
+                    .map_err(|e| format!("invalid number of blinks `{}`: {}", blinks, e)),
+                _ => Err("usage: blink <n>".to_string()),
+            }
+        })
+        .run();
 }
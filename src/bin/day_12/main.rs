@@ -1,4 +1,6 @@
 use std::collections::HashSet;
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::Day;
 
 trait Grid<T> {
     fn at(&self, pos: (isize, isize)) -> Option<T>;
@@ -104,24 +106,38 @@ fn flood_fill(grid: &Vec<Vec<char>>, start: (isize, isize), visited: &mut HashSe
     (area, perimeter, corners.len() + diagonals)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::grid(input);
+    problems.extend(validate::cells(input, "a plant", |c| c.is_ascii_uppercase()));
+    problems
+}
 
-    let grid = input.lines()
+fn parse(input: &str) -> Vec<Vec<char>> {
+    input.lines()
         .map(|line| line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
 
+fn costs(grid: &Vec<Vec<char>>) -> (usize, usize) {
     let mut visited = HashSet::new();
 
-    let (cost_1, cost_2) = grid.grid_iter().fold((0, 0), |(cost_1, cost_2), (_, pos)| {
+    grid.grid_iter().fold((0, 0), |(cost_1, cost_2), (_, pos)| {
         if visited.contains(&pos) {
             return (cost_1, cost_2);
         }
         // Euler's formula in 2D: V - E = 0
-        let (area, perimeter, vertices) = flood_fill(&grid, pos, &mut visited);
-        (cost_1 + area * perimeter, cost_2 + area * vertices)
-    });
+        let (area, perimeter, vertices) = flood_fill(grid, pos, &mut visited);
+        (
+            checked::add(cost_1, checked::mul(area, perimeter)),
+            checked::add(cost_2, checked::mul(area, vertices)),
+        )
+    })
+}
 
-    println!("Part 1: {}", cost_1);
-    println!("Part 2: {}", cost_2);
+fn main() {
+    Day::new("day_12", include_str!("input.txt"), validate, parse)
+        .part("Part 1", |grid, _| costs(grid).0)
+        .part("Part 2", |grid, _| costs(grid).1)
+        .show_grid("the garden", |grid| grid)
+        .run();
 }
@@ -1,3 +1,5 @@
+use advent_of_code_2024::checked;
+use advent_of_code_2024::Day;
 use regex::Regex;
 
 fn tokens(claw_machines: impl IntoIterator<Item=((usize, usize), (usize, usize), (usize, usize))>) -> usize {
@@ -13,20 +15,52 @@ fn tokens(claw_machines: impl IntoIterator<Item=((usize, usize), (usize, usize),
         const EPSILON: f64 = 0.01;
 
         if (a - a.round()).abs() < EPSILON && (b - b.round()).abs() < EPSILON {
-            tokens + 3 * a.round() as usize + b.round() as usize
+            checked::add(tokens, checked::add(checked::mul(3, a.round() as usize), b.round() as usize))
         } else {
             tokens
         }
     })
 }
 
-fn main() {
+type ClawMachine = ((usize, usize), (usize, usize), (usize, usize));
+
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected claw machines".to_string()];
+    }
+
+    let expected = [
+        ("Button A: X+<n>, Y+<n>", Regex::new(r"^Button A: X\+\d+, Y\+\d+$").unwrap()),
+        ("Button B: X+<n>, Y+<n>", Regex::new(r"^Button B: X\+\d+, Y\+\d+$").unwrap()),
+        ("Prize: X=<n>, Y=<n>", Regex::new(r"^Prize: X=\d+, Y=\d+$").unwrap()),
+    ];
+
+    let mut problems = Vec::new();
+    let mut first_line = 1;
+
+    for machine in input.split("\n\n") {
+        let lines = machine.lines().collect::<Vec<_>>();
+        if lines.len() != expected.len() {
+            problems.push(format!("line {}: a machine of {} lines, expected {}", first_line, lines.len(), expected.len()));
+        }
+
+        for (y, (line, (format, regex))) in lines.iter().zip(&expected).enumerate() {
+            if !regex.is_match(line) {
+                problems.push(format!("line {}: `{}` is not `{}`", first_line + y, line, format));
+            }
+        }
+
+        first_line += lines.len() + 1;
+    }
+
+    problems
+}
+
+fn parse(input: &str) -> Vec<ClawMachine> {
     let button_regex: Regex = Regex::new(r"Button .: X\+(\d+), Y\+(\d+)").unwrap();
     let prize_regex: Regex = Regex::new(r"Prize: X=(\d+), Y=(\d+)").unwrap();
 
-    let input = include_str!("input.txt");
-
-    let claw_machines = input.split("\n\n").map(|machine| {
+    input.split("\n\n").map(|machine| {
         let mut line = machine.splitn(3, '\n');
         let [button_a, button_b] = [line.next().unwrap(), line.next().unwrap()]
             .map(|button| {
@@ -43,14 +77,21 @@ fn main() {
         );
 
         (button_a, button_b, prize)
-    }).collect::<Vec<_>>();
-
-    println!("Part 1: {}", tokens(claw_machines.iter().copied()));
+    }).collect::<Vec<_>>()
+}
 
-    let claw_machines = claw_machines.into_iter().map(|(button_a, button_b, prize)| {
-        let prize = (prize.0 + 10000000000000, prize.1 + 10000000000000);
-        (button_a, button_b, prize)
-    });
+fn main() {
+    Day::new("day_13", include_str!("input.txt"), validate, parse)
+        .param("offset", 10000000000000_usize)
+        .part("Part 1", |claw_machines, _| tokens(claw_machines.iter().copied()))
+        .part("Part 2", |claw_machines, context| {
+            let offset = context.param::<usize>("offset");
+            let claw_machines = claw_machines.iter().map(|&(button_a, button_b, prize)| {
+                let prize = (checked::add(prize.0, offset), checked::add(prize.1, offset));
+                (button_a, button_b, prize)
+            });
 
-    println!("Part 2: {}", tokens(claw_machines));
+            tokens(claw_machines)
+        })
+        .run();
 }
@@ -1,24 +1,47 @@
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
 use regex::Regex;
 
-fn main() {
-    let robot_regex = Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
+type Robot = (i32, i32, i32, i32);
 
-    let input = include_str!("input.txt");
-    let (width, height) = (101, 103);
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected robots".to_string()];
+    }
 
-    let mut robots = input.lines().map(|line| {
+    let robot_regex = Regex::new(r"^p=\d+,\d+ v=-?\d+,-?\d+$").unwrap();
+    validate::lines(input, 1, |robot| {
+        if robot_regex.is_match(robot) {
+            Ok(())
+        } else {
+            Err(format!("`{}` is not p=<x>,<y> v=<x>,<y>", robot))
+        }
+    })
+}
+
+fn parse(input: &str) -> Vec<Robot> {
+    let robot_regex = Regex::new(r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)").unwrap();
+
+    input.lines().map(|line| {
         let captures = robot_regex.captures(line).unwrap();
         let x = captures.get(1).unwrap().as_str().parse::<i32>().unwrap();
         let y = captures.get(2).unwrap().as_str().parse::<i32>().unwrap();
         let vx = captures.get(3).unwrap().as_str().parse::<i32>().unwrap();
         let vy = captures.get(4).unwrap().as_str().parse::<i32>().unwrap();
         (x, y, vx, vy)
-    }).collect::<Vec<_>>();
+    }).collect::<Vec<_>>()
+}
 
-    let safety_factor: u32 = robots.iter()
-        .map(|(x, y, vx, vy)| {
-            ((x + 100 * vx).rem_euclid(width), (y + 100 * vy).rem_euclid(height))
+fn safety_factor(robots: &Vec<Robot>, context: &Context) -> u32 {
+    let (width, height) = (context.param::<i32>("width"), context.param::<i32>("height"));
+    let seconds = context.param::<i32>("seconds");
+
+    let quadrants = robots.iter()
+        .map(|&(x, y, vx, vy)| {
+            let x = checked::add(x, checked::mul(seconds, vx));
+            let y = checked::add(y, checked::mul(seconds, vy));
+            (x.rem_euclid(width), y.rem_euclid(height))
         })
         .fold([0, 0, 0, 0], |quadrants, robot| {
             let half_width = width as f32 / 2.0;
@@ -36,11 +59,20 @@ fn main() {
                 (false, true, false, true) => [quadrants[0], quadrants[1], quadrants[2], quadrants[3] + 1],
                 _ => quadrants,
             }
-        }).iter().product();
+        });
+
+    checked::product(quadrants)
+}
 
-    println!("Part 1: {}", safety_factor);
+fn christmas_tree(robots: &Vec<Robot>, context: &Context) -> usize {
+    let (width, height) = (context.param::<i32>("width"), context.param::<i32>("height"));
+    let tree_height = context.param::<usize>("tree_height");
+
+    let mut robots = robots.clone();
 
     for step in 1.. {
+        context.step();
+
         for (x, y, vx, vy) in &mut robots {
             *x = (*x + *vx).rem_euclid(width);
             *y = (*y + *vy).rem_euclid(height);
@@ -51,13 +83,25 @@ fn main() {
         for ((_, y1, ..), (_, y2, ..)) in robots.iter().tuple_windows() {
             if *y2 == y1 + 1 {
                 contiguous += 1;
-                if contiguous >= 30 {
-                    println!("Part 2: {}", step);
-                    return;
+                if contiguous >= tree_height {
+                    return step;
                 }
             } else {
                 contiguous = 1;
             }
         }
     }
+
+    unreachable!()
+}
+
+fn main() {
+    Day::new("day_14", include_str!("input.txt"), validate, parse)
+        .param("width", 101)
+        .param("height", 103)
+        .param("seconds", 100)
+        .param("tree_height", 30)
+        .part("Part 1", safety_factor)
+        .part("Part 2", christmas_tree)
+        .run();
 }
@@ -1,3 +1,6 @@
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
+
 trait Grid<T> {
     fn at(&self, pos: (isize, isize)) -> Option<T>;
     fn grid_iter(&self) -> GridIterator<T>;
@@ -150,18 +153,35 @@ fn run(grid: &mut Vec<Vec<char>>, movements: &Vec<char>) {
 }
 
 fn gps_sum(grid: &Vec<Vec<char>>, char: char) -> isize {
-    grid.grid_iter()
+    checked::sum(grid.grid_iter()
         .filter(|(cell, _)| *cell == char)
-        .map(|(_, (x, y))| 100 * y + x)
-        .sum::<isize>()
+        .map(|(_, (x, y))| checked::add(checked::mul(100, y), x)))
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::blank_lines(input, 1);
+
+    let Some((grid, movements)) = input.split_once("\n\n") else {
+        return problems;
+    };
+
+    problems.extend(validate::grid(grid));
+    problems.extend(validate::cells(grid, "a wall, a box, the robot or `.`", |c| "#O@.".contains(c)));
+    problems.extend(validate::exactly_one(grid, '@'));
+    problems.extend(validate::lines(movements, grid.lines().count() + 2, |line| {
+        match line.chars().find(|c| !"^>v<".contains(*c)) {
+            Some(c) => Err(format!("`{}` is not a movement", c)),
+            None => Ok(()),
+        }
+    }));
+
+    problems
+}
 
+fn parse(input: &str) -> (Vec<Vec<char>>, Vec<char>) {
     let (grid, movements) = input.split_once("\n\n").unwrap();
 
-    let starting_grid = grid.lines()
+    let grid = grid.lines()
         .map(|line| line.chars().collect::<Vec<_>>())
         .collect::<Vec<_>>();
 
@@ -170,12 +190,18 @@ fn main() {
         .flatten()
         .collect::<Vec<_>>();
 
+    (grid, movements)
+}
+
+fn part_1((starting_grid, movements): &(Vec<Vec<char>>, Vec<char>), _: &Context) -> isize {
     let mut grid = starting_grid.clone();
-    run(&mut grid, &movements);
-    println!("Part 1: {}", gps_sum(&grid, 'O'));
+    run(&mut grid, movements);
+    gps_sum(&grid, 'O')
+}
 
-    let mut grid = starting_grid.into_iter()
-        .map(|row| row.into_iter()
+fn part_2((starting_grid, movements): &(Vec<Vec<char>>, Vec<char>), _: &Context) -> isize {
+    let mut grid = starting_grid.iter()
+        .map(|row| row.iter()
             .flat_map(|cell| {
                 match cell {
                     '#' => "##",
@@ -188,6 +214,14 @@ fn main() {
             .collect::<Vec<_>>())
         .collect::<Vec<_>>();
 
-    run(&mut grid, &movements);
-    println!("Part 2: {}", gps_sum(&grid, '['));
+    run(&mut grid, movements);
+    gps_sum(&grid, '[')
+}
+
+fn main() {
+    Day::new("day_15", include_str!("input.txt"), validate, parse)
+        .part("Part 1", part_1)
+        .part("Part 2", part_2)
+        .show_grid("the warehouse", |(grid, _)| grid)
+        .run();
 }
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use advent_of_code_2024::{checked, validate, Context, Day};
 
 trait Grid<T> {
     fn at(&self, pos: (isize, isize)) -> Option<T>;
@@ -49,7 +50,12 @@ impl<'a, T: Copy> Iterator for GridIterator<'a, T> {
     }
 }
 
-fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -> (isize, isize) {
+fn dijkstra(
+    grid: &Vec<Vec<char>>,
+    start: (isize, isize),
+    end: (isize, isize),
+    context: &Context,
+) -> Option<(isize, HashSet<(isize, isize)>)> {
     let mut queue = Vec::new();
     let mut costs = HashMap::new();
     let mut previous = HashMap::new();
@@ -59,6 +65,8 @@ fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -
     queue.push((start, (1, 0)));
 
     while let Some((pos, direction)) = queue.pop() {
+        context.step();
+
         let cost = costs[&pos];
 
         if pos == end {
@@ -74,36 +82,85 @@ fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -
         for next_direction in [direction, (direction.1, direction.0), (-direction.1, -direction.0)] {
             let next = (pos.0 + next_direction.0, pos.1 + next_direction.1);
 
-            if !matches!(grid.at(next), Some('.' | 'E')) {
+            if !matches!(grid.at(next), Some('.' | 'E')) && next != end {
                 continue;
             }
 
-            let next_cost = cost + 1
-                + if next_direction != direction { 1000 } else { 0 };
+            let next_cost = checked::add(cost, 1 + if next_direction != direction { 1000 } else { 0 });
 
             if next_cost < *costs.entry(next).or_insert(isize::MAX) ||
-                next_cost - 1000 <= *costs.entry(next).or_insert(isize::MAX) {
+                checked::sub(next_cost, 1000) <= *costs.entry(next).or_insert(isize::MAX) {
                 queue.push((next, next_direction));
                 costs.insert(next, next_cost);
                 previous.insert(next, pos);
             }
         }
     }
-    (costs[&end], best_tiles[&costs[&end]].len() as isize)
+    let cost = *costs.get(&end)?;
+    Some((cost, best_tiles.remove(&cost).unwrap()))
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::grid(input);
+    problems.extend(validate::exactly_one(input, 'S'));
+    problems.extend(validate::exactly_one(input, 'E'));
+    problems
+}
 
-    let grid = input.lines()
+fn parse(input: &str) -> Vec<Vec<char>> {
+    input.lines()
         .map(|line| line.chars()
             .collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
 
+fn solve(grid: &Vec<Vec<char>>, context: &Context) -> (isize, HashSet<(isize, isize)>) {
     let start = grid.grid_iter().find(|(cell, _)| *cell == 'S').unwrap().1;
     let end = grid.grid_iter().find(|(cell, _)| *cell == 'E').unwrap().1;
 
-    let (cost, best_tiles) = dijkstra(&grid, start, end);
-    println!("Part 1: {}", cost);
-    println!("Part 2: {}", best_tiles);
+    dijkstra(grid, start, end, context).unwrap()
+}
+
+/// A tile given either as its letter, like `S`, or as `x,y`.
+fn tile(grid: &Vec<Vec<char>>, tile: &str) -> Result<(isize, isize), String> {
+    if let Some((x, y)) = tile.split_once(',') {
+        let pos = (x.parse().map_err(|_| format!("invalid x in `{}`", tile))?,
+                   y.parse().map_err(|_| format!("invalid y in `{}`", tile))?);
+        return grid.at(pos).map(|_| pos).ok_or_else(|| format!("`{}` is outside the maze", tile));
+    }
+
+    grid.grid_iter()
+        .find(|(cell, _)| tile.len() == 1 && tile.starts_with(*cell))
+        .map(|(_, pos)| pos)
+        .ok_or_else(|| format!("no `{}` tile in the maze", tile))
+}
+
+fn path(grid: &Vec<Vec<char>>, from: &str, to: &str, context: &Context) -> Result<String, String> {
+    let (start, end) = (tile(grid, from)?, tile(grid, to)?);
+
+    let Some((cost, best_tiles)) = dijkstra(grid, start, end, context) else {
+        return Ok(format!("no path from {} to {}", from, to));
+    };
+
+    let mut grid = grid.clone();
+    for &(x, y) in &best_tiles {
+        if grid[y as usize][x as usize] == '.' {
+            grid[y as usize][x as usize] = 'O';
+        }
+    }
+
+    let rendering = grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n");
+    Ok(format!("{}\nscore {}, {} tiles on the best paths", rendering, cost, best_tiles.len()))
+}
+
+fn main() {
+    Day::new("day_16", include_str!("input.txt"), validate, parse)
+        .part("Part 1", |grid, context| solve(grid, context).0)
+        .part("Part 2", |grid, context| solve(grid, context).1.len())
+        .show_grid("the maze", |grid| grid)
+        .command("path", "path from <tile> to <tile>: best paths between two tiles, given as a letter or x,y", |grid, context, args| match args {
+            ["from", from, "to", to] => path(grid, from, to, context),
+            _ => Err("usage: path from <tile> to <tile>".to_string()),
+        })
+        .run();
 }
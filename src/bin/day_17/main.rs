@@ -1,4 +1,6 @@
 use std::ops::BitXor;
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
 
 fn combo(n: usize, registers: &[usize]) -> usize {
@@ -14,16 +16,18 @@ fn combo(n: usize, registers: &[usize]) -> usize {
     a
 }
 
-fn run(registers: &mut [usize], program: &[usize]) -> Vec<usize> {
+fn run(registers: &mut [usize], program: &[usize], context: &Context) -> Vec<usize> {
     let mut ip = 0;
     let mut output = Vec::new();
 
     loop {
+        context.step();
+
         if ip >= program.len() {
             break;
         }
         match program[ip] {
-            0 => registers[0] = registers[0] >> combo(program[ip + 1], &registers),
+            0 => registers[0] = checked::shr(registers[0], combo(program[ip + 1], &registers)),
             1 => registers[1] = registers[1].bitxor(program[ip + 1]),
             2 => registers[1] = combo(program[ip + 1], &registers) % 8,
             3 => {
@@ -36,8 +40,8 @@ fn run(registers: &mut [usize], program: &[usize]) -> Vec<usize> {
             }
             4 => registers[1] = registers[1].bitxor(registers[2]),
             5 => output.push(combo(program[ip + 1], &registers) % 8),
-            6 => registers[1] = registers[0] >> combo(program[ip + 1], &registers),
-            7 => registers[2] = registers[0] >> combo(program[ip + 1], &registers),
+            6 => registers[1] = checked::shr(registers[0], combo(program[ip + 1], &registers)),
+            7 => registers[2] = checked::shr(registers[0], combo(program[ip + 1], &registers)),
             _ => unreachable!(),
         }
         ip += 2;
@@ -58,7 +62,7 @@ fn run_backwards(program: &[usize]) -> usize {
     for ip in (0..unrolled_program.len()).step_by(2).rev() {
         let arg = unrolled_program[ip + 1];
         match unrolled_program[ip] {
-            0 => registers[0] <<= combo(arg, &registers),
+            0 => registers[0] = checked::shl(registers[0], combo(arg, &registers)),
             1 => registers[1] = registers[1].bitxor(arg),
             2 => {
                 assert!(registers[1] <= 7);
@@ -74,8 +78,8 @@ fn run_backwards(program: &[usize]) -> usize {
                     _ => {}
                 }
             }
-            6 => registers[1] = registers[0] << combo(arg, &registers),
-            7 => registers[2] = registers[0] << combo(arg, &registers),
+            6 => registers[1] = checked::shl(registers[0], combo(arg, &registers)),
+            7 => registers[2] = checked::shl(registers[0], combo(arg, &registers)),
             _ => {},
         }
     }
@@ -107,12 +111,41 @@ fn unroll_loops(program: &[usize]) -> Vec<usize> {
     unrolled_program
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::blank_lines(input, 1);
+
+    let Some((registers, program)) = input.split_once("\n\n") else {
+        return problems;
+    };
+
+    if registers.lines().count() != 3 {
+        problems.push(format!("{} registers, expected A, B and C", registers.lines().count()));
+    }
+    problems.extend(validate::lines(registers, 1, |register| {
+        let (name, value) = register.split_once(": ").unwrap_or((register, ""));
+        if !["Register A", "Register B", "Register C"].contains(&name) || value.parse::<usize>().is_err() {
+            Err(format!("`{}` is not Register <name>: <value>", register))
+        } else {
+            Ok(())
+        }
+    }));
+    problems.extend(validate::lines(program, registers.lines().count() + 2, |line| {
+        let Some(program) = line.strip_prefix("Program: ") else {
+            return Err(format!("`{}` is not Program: <3-bit numbers>", line));
+        };
+        match program.split(',').find(|n| !matches!(n.parse::<usize>(), Ok(0..=7))) {
+            Some(n) => Err(format!("`{}` is not a 3-bit number", n)),
+            None => Ok(()),
+        }
+    }));
+
+    problems
+}
 
+fn parse(input: &str) -> (Vec<usize>, Vec<usize>) {
     let (registers, program) = input.split_once("\n\n").unwrap();
 
-    let mut registers = registers.lines().map(|line| {
+    let registers = registers.lines().map(|line| {
         line.split_once(": ").unwrap().1.parse::<usize>().unwrap()
     }).collect::<Vec<_>>();
 
@@ -122,9 +155,33 @@ fn main() {
         .map(|n| n.parse::<usize>().unwrap())
         .collect::<Vec<_>>();
 
-    let output = run(&mut registers, &program);
-    println!("Part 1: {}", output.iter().join(","));
+    (registers, program)
+}
+
+fn main() {
+    Day::new("day_17", include_str!("input.txt"), validate, parse)
+        .part("Part 1", |(registers, program), context| {
+            let output = run(&mut registers.clone(), program, context);
+            output.iter().join(",")
+        })
+        .part("Part 2", |(_, program), _| run_backwards(program))
+        .command("run", "run [a=<n>] [b=<n>] [c=<n>]: output of the program with the given registers", |(registers, program), context, args| {
+            let mut registers = registers.clone();
+            for arg in args {
+                let (register, value) = arg.split_once('=')
+                    .ok_or_else(|| format!("malformed register `{}`, expected <register>=<n>", arg))?;
+                let index = match register {
+                    "a" | "A" => 0,
+                    "b" | "B" => 1,
+                    "c" | "C" => 2,
+                    _ => return Err(format!("unknown register `{}`, expected a, b or c", register)),
+                };
+                registers[index] = value.parse()
+                    .map_err(|e| format!("invalid value `{}` for register {}: {}", value, register, e))?;
+            }
 
-    let output = run_backwards(&program);
-    println!("Part 2: {}", output);
+            let output = run(&mut registers, program, context);
+            Ok(format!("{} (registers after halting: {})", output.iter().join(","), registers.iter().join(", ")))
+        })
+        .run();
 }
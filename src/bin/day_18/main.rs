@@ -1,8 +1,9 @@
 use std::collections::{HashMap, HashSet};
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{render_grid, Context, Day};
 
 trait Grid<T> {
     fn at(&self, pos: (isize, isize)) -> Option<T>;
-    fn grid_iter(&self) -> GridIterator<T>;
 }
 
 impl<T: Copy> Grid<T> for Vec<Vec<T>> {
@@ -13,40 +14,9 @@ impl<T: Copy> Grid<T> for Vec<Vec<T>> {
         self.get(y as usize).and_then(|row|
             row.get(x as usize).copied())
     }
-
-    fn grid_iter(&self) -> GridIterator<T> {
-        GridIterator {
-            grid: self,
-            x: 0,
-            y: 0,
-        }
-    }
-}
-
-struct GridIterator<'a, T> {
-    grid: &'a Vec<Vec<T>>,
-    x: isize,
-    y: isize,
-}
-
-impl<'a, T: Copy> Iterator for GridIterator<'a, T> {
-    type Item = (T, (isize, isize));
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let cell = self.grid.at((self.x, self.y))?;
-        let ret = Some((cell, (self.x, self.y)));
-
-        self.x += 1;
-        if self.x >= self.grid[0].len() as isize {
-            self.x = 0;
-            self.y += 1;
-        }
-
-        ret
-    }
 }
 
-fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -> Option<isize> {
+fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize), context: &Context) -> Option<isize> {
     let mut queue = Vec::new();
     let mut costs = HashMap::new();
 
@@ -54,6 +24,8 @@ fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -
     queue.push(start);
 
     while let Some(pos) = queue.pop() {
+        context.step();
+
         let cost = costs[&pos];
 
         for next_direction in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
@@ -63,7 +35,7 @@ fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -
                 continue;
             }
 
-            let next_cost = cost + 1;
+            let next_cost = checked::add(cost, 1);
 
             if next_cost < *costs.entry(next).or_insert(isize::MAX) {
                 queue.push(next);
@@ -74,13 +46,15 @@ fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -
     costs.get(&end).copied()
 }
 
-fn can_reach(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -> bool {
+fn can_reach(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize), context: &Context) -> bool {
     let mut visited = HashSet::new();
     let mut queue = Vec::new();
 
     queue.push(start);
 
     while let Some(pos) = queue.pop() {
+        context.step();
+
         if pos == end {
             return true;
         }
@@ -104,27 +78,74 @@ fn can_reach(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize))
     false
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected falling bytes".to_string()];
+    }
 
-    let coords = input.lines().map(|line| {
+    validate::lines(input, 1, |byte| {
+        let position = byte.split_once(',')
+            .and_then(|(x, y)| Some((x.parse::<usize>().ok()?, y.parse::<usize>().ok()?)));
+        match position {
+            Some(_) => Ok(()),
+            None => Err(format!("`{}` is not <x>,<y>", byte)),
+        }
+    })
+}
+
+fn parse(input: &str) -> Vec<(isize, isize)> {
+    input.lines().map(|line| {
         let (x, y) = line.split_once(',').unwrap();
         (x.parse::<isize>().unwrap(), y.parse::<isize>().unwrap())
-    }).collect::<Vec<_>>();
+    }).collect::<Vec<_>>()
+}
 
-    let mut memory = vec![vec!['.'; 71]; 71];
-    for (x, y) in coords.iter().take(1024) {
+fn memory(coords: &[(isize, isize)], context: &Context) -> (Vec<Vec<char>>, (isize, isize)) {
+    let size = context.param::<usize>("size");
+
+    if let Some((x, y)) = coords.iter().find(|&&(x, y)| x as usize >= size || y as usize >= size) {
+        context.fail(format!("byte {},{} falls outside of a memory space of size {}", x, y, size));
+    }
+
+    let mut memory = vec![vec!['.'; size]; size];
+    for (x, y) in coords.iter().take(context.param("bytes")) {
         memory[*x as usize][*y as usize] = '#';
     }
 
-    let steps = dijkstra(&memory, (0, 0), (70, 70));
-    println!("Part 1: {}", steps.unwrap());
+    (memory, (size as isize - 1, size as isize - 1))
+}
+
+fn part_1(coords: &Vec<(isize, isize)>, context: &Context) -> isize {
+    let (memory, exit) = memory(coords, context);
+    dijkstra(&memory, (0, 0), exit, context).unwrap_or_else(|| context.fail("no path to the exit"))
+}
+
+fn part_2(coords: &Vec<(isize, isize)>, context: &Context) -> String {
+    let (mut memory, exit) = memory(coords, context);
 
-    for (x, y) in coords.iter().skip(1024) {
+    for (x, y) in coords.iter().skip(context.param("bytes")) {
         memory[*x as usize][*y as usize] = '#';
-        if can_reach(&memory, (0, 0), (70, 70)) {
-            println!("Part 2: {},{}", x, y);
-            break;
+        if can_reach(&memory, (0, 0), exit, context) {
+            return format!("{},{}", x, y);
         }
     }
+
+    context.fail("no path to the exit")
+}
+
+fn main() {
+    Day::new("day_18", include_str!("input.txt"), validate, parse)
+        .param("size", 71)
+        .param("bytes", 1024)
+        .part("Part 1", part_1)
+        .part("Part 2", part_2)
+        .command("show", "show grid: print the memory space once the first bytes have fallen", |coords, context, args| match args {
+            ["grid"] => {
+                let (memory, _) = memory(coords, context);
+                let rows = (0..memory.len()).map(|y| memory.iter().map(|column| column[y]).collect()).collect::<Vec<Vec<char>>>();
+                Ok(render_grid(&rows))
+            }
+            _ => Err("usage: show grid".to_string()),
+        })
+        .run();
 }
@@ -1,11 +1,14 @@
 #![allow(unstable_name_collisions)]
 
 use std::collections::HashMap;
+use advent_of_code_2024::checked::{self, Wide};
+use advent_of_code_2024::validate;
+use advent_of_code_2024::Day;
 use itertools::Itertools;
 use regex::Regex;
 
-fn match_count<'a>(patterns: &[&str], design: &'a str, cache: &mut HashMap<&'a str, usize>) -> usize {
-    patterns.iter()
+fn match_count<'a>(patterns: &[&str], design: &'a str, cache: &mut HashMap<&'a str, Wide>) -> Wide {
+    checked::sum(patterns.iter()
         .filter(|&&pattern| design.starts_with(pattern))
         .map(|&pattern| {
             if design == pattern {
@@ -20,28 +23,59 @@ fn match_count<'a>(patterns: &[&str], design: &'a str, cache: &mut HashMap<&'a s
             let count = match_count(patterns, rest, cache);
             cache.insert(rest, count);
             count
-        })
-        .sum()
+        }))
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::blank_lines(input, 1);
+
+    let Some((patterns, designs)) = input.split_once("\n\n") else {
+        return problems;
+    };
+
+    let stripes = |towel: &str| match towel.chars().find(|c| !"wubrg".contains(*c)) {
+        Some(c) => Err(format!("`{}` is not a stripe colour, expected one of w, u, b, r or g", c)),
+        None if towel.is_empty() => Err("empty towel".to_string()),
+        None => Ok(()),
+    };
+
+    if patterns.lines().count() != 1 {
+        problems.push(format!("{} lines of towel patterns, expected 1", patterns.lines().count()));
+    }
+    problems.extend(validate::lines(patterns, 1, |patterns| patterns.split(", ").try_for_each(stripes)));
+    problems.extend(validate::lines(designs, patterns.lines().count() + 2, stripes));
+
+    problems
+}
 
+fn parse(input: &str) -> (Vec<&str>, Vec<&str>) {
     let (patterns, designs) = input.split_once("\n\n").unwrap();
     let patterns = patterns.split(", ").collect::<Vec<_>>();
     let designs = designs.lines().collect::<Vec<_>>();
 
-    let or = patterns.iter().intersperse(&"|").copied().collect::<String>();
-    let regex = Regex::new(&format!("^({})+$", or)).unwrap();
+    (patterns, designs)
+}
 
-    let count = designs.iter().filter(|design| regex.is_match(design)).count();
-    println!("Part 1: {}", count);
+fn main() {
+    Day::new("day_19", include_str!("input.txt"), validate, parse)
+        .part("Part 1", |(patterns, designs), _| {
+            let or = patterns.iter().intersperse(&"|").copied().collect::<String>();
+            let regex = Regex::new(&format!("^({})+$", or)).unwrap();
 
-    let mut cache = HashMap::new();
+            designs.iter().filter(|design| regex.is_match(design)).count()
+        })
+        .alternative("match_count", |(patterns, designs), _| {
+            let mut cache = HashMap::new();
 
-    let count: usize = designs.iter()
-        .map(|design| match_count(&patterns, design, &mut cache))
-        .sum();
+            designs.iter()
+                .filter(|design| match_count(patterns, design, &mut cache) > 0)
+                .count()
+        })
+        .part("Part 2", |(patterns, designs), _| {
+            let mut cache = HashMap::new();
 
-    println!("Part 2: {}", count);
+            checked::sum(designs.iter()
+                .map(|design| match_count(patterns, design, &mut cache)))
+        })
+        .run();
 }
@@ -1,29 +1,117 @@
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use advent_of_code_2024::validate;
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
 
+/// Why a pair of levels is unsafe.
+#[derive(Copy, Clone)]
+enum Fault {
+    TooFar { distance: usize, max: usize },
+    TooClose { distance: usize, min: usize },
+    Flat,
+    Reversed,
+}
+
+impl Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fault::TooFar { distance, max } => write!(f, "a step of {} is more than {}", distance, max),
+            Fault::TooClose { distance, min } => write!(f, "a step of {} is less than {}", distance, min),
+            Fault::Flat => write!(f, "the levels are equal"),
+            Fault::Reversed => write!(f, "the trend is reversed"),
+        }
+    }
+}
+
+/// What makes a step between two levels safe.
+trait SafetyPolicy {
+    /// Why the step from `first` to `second` is unsafe, given the `trend` of the steps before.
+    fn fault(&self, first: usize, second: usize, trend: Option<Ordering>) -> Option<Fault>;
+
+    /// The largest safe step.
+    fn reach(&self) -> usize;
+}
+
+/// Steps of `min` to `max` in a single direction, allowing equal levels with `plateaus`.
+///
+/// The default is the rule of the puzzle.
+struct Steps {
+    min: usize,
+    max: usize,
+    plateaus: bool,
+}
+
+impl Default for Steps {
+    fn default() -> Self {
+        Self {
+            min: 1,
+            max: 3,
+            plateaus: false,
+        }
+    }
+}
+
+impl Steps {
+    fn from_context(context: &Context) -> Self {
+        Self {
+            min: context.param("min_step"),
+            max: context.param("max_step"),
+            plateaus: context.param("plateaus"),
+        }
+    }
+}
+
+impl SafetyPolicy for Steps {
+    fn fault(&self, first: usize, second: usize, trend: Option<Ordering>) -> Option<Fault> {
+        let distance = first.abs_diff(second);
+        let ordering = second.cmp(&first);
+
+        if ordering == Ordering::Equal {
+            (!self.plateaus).then_some(Fault::Flat)
+        } else if distance > self.max {
+            Some(Fault::TooFar { distance, max: self.max })
+        } else if distance < self.min {
+            Some(Fault::TooClose { distance, min: self.min })
+        } else if trend.is_some_and(|trend| trend != ordering) {
+            Some(Fault::Reversed)
+        } else {
+            None
+        }
+    }
+
+    fn reach(&self) -> usize {
+        self.max
+    }
+}
+
 #[derive(Copy, Clone)]
 struct Observation {
     safe: bool,
+    fault: Option<Fault>,
     levels: (usize, usize),
+    /// Direction of the steps so far, which plateaus do not change.
+    trend: Option<Ordering>,
 }
 
 impl Observation {
-    fn new(first: usize, second: usize, previous_observation: Option<Self>) -> Self {
-        let distance = first.abs_diff(second);
-        let ordering = second.cmp(&first);
+    fn new(first: usize, second: usize, previous_observation: Option<Self>, policy: &dyn SafetyPolicy) -> Self {
+        let trend = previous_observation.and_then(|observation| observation.trend);
+        let fault = policy.fault(first, second, trend);
 
-        let mut safe = distance <= 3 && ordering != Ordering::Equal;
+        let mut observation = Self {
+            safe: fault.is_none() && previous_observation.is_none_or(|observation| observation.safe),
+            fault,
+            levels: (first, second),
+            trend,
+        };
 
-        if let Some(previous_observation) = previous_observation {
-            safe = safe
-                && previous_observation.safe
-                && previous_observation.ordering() == ordering;
+        if observation.ordering() != Ordering::Equal {
+            observation.trend = Some(observation.ordering());
         }
 
-        Self {
-            safe,
-            levels: (first, second),
-        }
+        observation
     }
 
     fn ordering(&self) -> Ordering {
@@ -32,18 +120,32 @@ impl Observation {
 }
 
 #[derive(Copy, Clone)]
-struct SafetyObserver {
+struct SafetyObserver<'a> {
+    policy: &'a dyn SafetyPolicy,
     observation: Option<Observation>,
+    /// Index of the first unsafe pair, with its fault.
+    first_fault: Option<(usize, Fault)>,
+    pairs: usize,
 }
 
-impl SafetyObserver {
-    fn new() -> Self {
-        Self { observation: None }
+impl<'a> SafetyObserver<'a> {
+    fn new(policy: &'a dyn SafetyPolicy) -> Self {
+        Self {
+            policy,
+            observation: None,
+            first_fault: None,
+            pairs: 0,
+        }
     }
 
     fn observe(self, left: usize, right: usize) -> Self {
+        let observation = Observation::new(left, right, self.observation, self.policy);
+
         Self {
-            observation: Some(Observation::new(left, right, self.observation)),
+            policy: self.policy,
+            observation: Some(observation),
+            first_fault: self.first_fault.or(observation.fault.map(|fault| (self.pairs, fault))),
+            pairs: self.pairs + 1,
         }
     }
 
@@ -53,101 +155,237 @@ impl SafetyObserver {
 }
 
 
+/// A way of removing levels seen so far that keeps the report safe.
 #[derive(Copy, Clone)]
-struct ToleranceObserver {
-    safety_observer: SafetyObserver,
-    candidates_for_elimination: Option<(usize, usize)>,
-    already_tolerated: bool,
+struct Branch<'a> {
+    safety_observer: SafetyObserver<'a>,
+    last: Option<usize>,
+    removed: usize,
 }
 
-impl ToleranceObserver {
-    fn new() -> Self {
+/// Observes the levels one at a time, following every way of removing up to `tolerance` of them.
+///
+/// Branches ending on the same level with the same trend behave alike from then on, so only the one
+/// with the fewest removals is kept.
+struct ToleranceObserver<'a> {
+    tolerance: usize,
+    branches: Vec<Branch<'a>>,
+}
+
+impl<'a> ToleranceObserver<'a> {
+    fn new(policy: &'a dyn SafetyPolicy, tolerance: usize) -> Self {
         Self {
-            safety_observer: SafetyObserver::new(),
-            candidates_for_elimination: None,
-            already_tolerated: false,
+            tolerance,
+            branches: vec![Branch { safety_observer: SafetyObserver::new(policy), last: None, removed: 0 }],
         }
     }
 
-    fn observe(self, left: usize, right: usize) -> Self {
-        if self.already_tolerated {
-            return self;
-        }
-
-        let safety_observer = self.safety_observer.observe(left, right);
+    fn observe(self, level: usize) -> Self {
+        let mut branches: HashMap<(Option<usize>, Option<Ordering>), Branch<'a>> = HashMap::new();
 
-        if safety_observer.is_safe() {
-            return Self {
-                safety_observer,
-                ..self
+        for branch in self.branches {
+            let kept = match branch.last {
+                Some(last) => Some(branch.safety_observer.observe(last, level)).filter(SafetyObserver::is_safe),
+                None => Some(branch.safety_observer),
             };
-        }
+            let kept = kept.map(|safety_observer| Branch { safety_observer, last: Some(level), ..branch });
+            let removed = (branch.removed < self.tolerance).then_some(Branch { removed: branch.removed + 1, ..branch });
 
-        match self.candidates_for_elimination {
-            None => Self {
-                candidates_for_elimination: Some((left, right)),
-                ..self
-            },
-
-            Some(candidates) => {
-                // Try to eliminate one of the candidates.
-                let safe_observer = [candidates.0, candidates.1]
-                    .map(|candidate| self.safety_observer.observe(candidate, right))
-                    .iter().filter(|observer| observer.is_safe())
-                    .next()
-                    .copied();
-
-                match safe_observer {
-                    // One of the candidates was successfully eliminated.
-                    Some(safety_observer) => {
-                        Self {
-                            safety_observer,
-                            already_tolerated: true,
-                            ..self
-                        }
-                    }
-                    // No luck, already unsafe.
-                    None => {
-                        Self {
-                            safety_observer,
-                            ..self
-                        }
-                    }
-                }
+            for branch in kept.into_iter().chain(removed) {
+                let trend = branch.safety_observer.observation.and_then(|observation| observation.trend);
+                branches.entry((branch.last, trend))
+                    .and_modify(|kept| if branch.removed < kept.removed { *kept = branch })
+                    .or_insert(branch);
             }
         }
+
+        Self {
+            tolerance: self.tolerance,
+            branches: branches.into_values().collect(),
+        }
     }
 
     fn is_safe(&self) -> bool {
-        self.safety_observer.is_safe() && !self.already_tolerated
+        !self.branches.is_empty()
     }
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+/// Minimum number of levels to remove for the report to be safe: its length minus its longest safe
+/// subsequence.
+///
+/// The longest safe subsequences are kept per direction and per last level. A safe step spans at
+/// most the reach of the policy, so extending them only looks at that many levels on either side,
+/// which keeps this linear for a given policy.
+fn min_removals(report: &[usize], policy: &dyn SafetyPolicy) -> usize {
+    let mut increasing: HashMap<usize, usize> = HashMap::new();
+    let mut decreasing: HashMap<usize, usize> = HashMap::new();
+    let mut longest = 0;
+
+    for &level in report {
+        let up = 1 + (level.saturating_sub(policy.reach())..=level)
+            .filter(|&previous| policy.fault(previous, level, Some(Ordering::Greater)).is_none())
+            .filter_map(|previous| increasing.get(&previous).copied())
+            .max()
+            .unwrap_or(0);
+        let down = 1 + (level..=level + policy.reach())
+            .filter(|&previous| policy.fault(previous, level, Some(Ordering::Less)).is_none())
+            .filter_map(|previous| decreasing.get(&previous).copied())
+            .max()
+            .unwrap_or(0);
 
-    let reports = input
+        let up_entry = increasing.entry(level).or_default();
+        *up_entry = (*up_entry).max(up);
+        let down_entry = decreasing.entry(level).or_default();
+        *down_entry = (*down_entry).max(down);
+
+        longest = longest.max(up).max(down);
+    }
+
+    report.len() - longest
+}
+
+fn observe(levels: impl Iterator<Item=usize>, policy: &dyn SafetyPolicy) -> SafetyObserver<'_> {
+    levels.tuple_windows().fold(SafetyObserver::new(policy), |observer, (left, right)| {
+        observer.observe(left, right)
+    })
+}
+
+/// Why the report is unsafe, and which single level could be removed to make it safe.
+fn explain(report: &[usize], policy: &dyn SafetyPolicy) -> String {
+    let levels = report.iter().join(" ");
+
+    let Some((pair, fault)) = observe(report.iter().copied(), policy).first_fault else {
+        return format!("{}: safe", levels);
+    };
+
+    let removable = (0..report.len()).find(|&removed| {
+        observe(report.iter()
+            .enumerate()
+            .filter(|&(index, _)| index != removed)
+            .map(|(_, &level)| level), policy)
+            .is_safe()
+    });
+
+    let fix = match removable {
+        Some(index) => format!("removing level {} ({}) makes it safe", index, report[index]),
+        None => "no single level can be removed to make it safe".to_string(),
+    };
+
+    format!(
+        "{}: unsafe at levels {} and {} ({} -> {}), {}; {}",
+        levels, pair, pair + 1, report[pair], report[pair + 1], fault, fix,
+    )
+}
+
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected reports of levels".to_string()];
+    }
+
+    validate::lines(input, 1, |report| {
+        if report.trim().is_empty() {
+            Err("empty report, expected levels separated by spaces".to_string())
+        } else if let Some(level) = report.split_whitespace().find(|level| level.parse::<usize>().is_err()) {
+            Err(format!("`{}` is not a level", level))
+        } else {
+            Ok(())
+        }
+    })
+}
+
+fn parse(input: &str) -> Vec<Vec<usize>> {
+    input
         .lines()
         .map(|report| report.split_whitespace()
             .map(|level| level.parse::<usize>().unwrap())
             .collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
 
-    let safe_reports = reports.iter().filter(|report| {
-        let observer = report.iter().tuple_windows().fold(SafetyObserver::new(), |observer, (&left, &right)| {
+fn safe_reports(reports: &Vec<Vec<usize>>, context: &Context) -> usize {
+    let policy = Steps::from_context(context);
+
+    reports.iter().filter(|report| {
+        let observer = report.iter().tuple_windows().fold(SafetyObserver::new(&policy), |observer, (&left, &right)| {
             observer.observe(left, right)
         });
         observer.is_safe()
-    });
+    }).count()
+}
 
-    println!("Safe reports: {}", safe_reports.count());
+fn tolerated_reports(reports: &Vec<Vec<usize>>, context: &Context) -> usize {
+    let policy = Steps::from_context(context);
+    let tolerance = context.param::<usize>("tolerance");
 
-    let tolerated_reports = reports.iter().filter(|report| {
-        let observer = report.iter().tuple_windows().fold(ToleranceObserver::new(), |observer, (&left, &right)| {
-            observer.observe(left, right)
+    reports.iter().filter(|report| min_removals(report, &policy) <= tolerance).count()
+}
+
+fn tolerated_reports_observer(reports: &Vec<Vec<usize>>, context: &Context) -> usize {
+    let policy = Steps::from_context(context);
+    let tolerance = context.param::<usize>("tolerance");
+
+    reports.iter().filter(|report| {
+        let observer = report.iter().fold(ToleranceObserver::new(&policy, tolerance), |observer, &level| {
+            observer.observe(level)
         });
         observer.is_safe()
-    });
+    }).count()
+}
+
+fn main() {
+    let puzzle_rule = Steps::default();
+
+    Day::new("day_2", include_str!("input.txt"), validate, parse)
+        .param("min_step", puzzle_rule.min)
+        .param("max_step", puzzle_rule.max)
+        .param("plateaus", puzzle_rule.plateaus)
+        .part("Safe reports", safe_reports)
+        .param("tolerance", 1)
+        .part("Tolerated reports", tolerated_reports)
+        .alternative("observer", tolerated_reports_observer)
+        .command("explain", "explain [<line>]: why each unsafe report, or the report on a line, is unsafe", |reports, context, args| {
+            let policy = Steps::from_context(context);
+
+            match args {
+                [] => Ok(reports.iter()
+                    .enumerate()
+                    .filter(|(_, report)| !observe(report.iter().copied(), &policy).is_safe())
+                    .map(|(index, report)| format!("line {}: {}", index + 1, explain(report, &policy)))
+                    .join("\n")),
+                [line] => line.parse::<usize>().ok()
+                    .and_then(|line| reports.get(line.checked_sub(1)?))
+                    .map(|report| explain(report, &policy))
+                    .ok_or_else(|| format!("no report on line `{}`", line)),
+                _ => Err("usage: explain [<line>]".to_string()),
+            }
+        })
+        .command("removals", "removals [reports]: how many reports need each number of removals to be safe, and with `reports` the number for each of them", |reports, context, args| {
+            let policy = Steps::from_context(context);
+            let removals = reports.iter()
+                .map(|report| {
+                    context.step();
+                    min_removals(report, &policy)
+                })
+                .collect::<Vec<_>>();
+
+            let mut distribution = BTreeMap::new();
+            for &removed in &removals {
+                *distribution.entry(removed).or_insert(0) += 1;
+            }
+
+            let mut lines = distribution.iter()
+                .map(|(removed, reports)| format!("{} removed: {} reports", removed, reports))
+                .collect::<Vec<_>>();
+
+            match args {
+                [] => {}
+                ["reports"] => lines.extend(removals.iter()
+                    .enumerate()
+                    .map(|(index, removed)| format!("report {}: {}", index + 1, removed))),
+                _ => return Err("usage: removals [reports]".to_string()),
+            }
 
-    println!("Tolerated reports: {}", tolerated_reports.count());
+            Ok(lines.join("\n"))
+        })
+        .run();
 }
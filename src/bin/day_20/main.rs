@@ -1,4 +1,5 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use advent_of_code_2024::{checked, validate, Context, Day};
 
 trait Grid<T> {
     fn at(&self, pos: (isize, isize)) -> Option<T>;
@@ -46,263 +47,185 @@ impl<'a, T: Copy> Iterator for GridIterator<'a, T> {
     }
 }
 
-type Shortcut = ((isize, isize), (isize, isize));
-
-fn browse_previouses(
+fn dijkstra(
+    grid: &Vec<Vec<char>>,
+    start: (isize, isize),
     end: (isize, isize),
+    context: &Context,
+) -> HashMap<isize, HashSet<(isize, isize)>> {
+    let mut shortcuts: HashMap<isize, HashSet<_>> = HashMap::new();
+
+    let mut queue = Vec::new();
+    let mut costs = HashMap::new();
+
+    costs.insert(start, 0);
+    queue.push(start);
+
+    let (base_distance, potential_shortcuts) = dijkstra_find_potential_shortcuts(grid, end, &mut queue, &mut costs, context);
+
+    potential_shortcuts.iter().for_each(|&potential_shortcut| {
+        let distance = dijkstra_with_shortcut(grid, start, end, potential_shortcut, context);
+        shortcuts.entry(checked::sub(base_distance, distance)).or_default().insert(potential_shortcut);
+    });
+
+    shortcuts
+}
+
+fn dijkstra_with_shortcut(
+    grid: &Vec<Vec<char>>,
     start: (isize, isize),
-    previouses: &HashMap<(isize, isize), HashSet<(isize, isize)>>,
-    shortcuts: &mut HashSet<Shortcut>,
-) {
-    if let Some(prev) = previouses.get(&start) {
-        for &previous in prev.iter() {
-            browse_previouses(end, previous, previouses, shortcuts);
+    end: (isize, isize),
+    shortcut: (isize, isize),
+    context: &Context,
+) -> isize {
+    let mut queue = Vec::new();
+    let mut costs = HashMap::new();
+
+    costs.insert(start, 0);
+    queue.push(start);
+
+    while let Some(pos) = queue.pop() {
+        context.step();
+
+        let cost = costs[&pos];
+
+        for next_direction in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+            let next = (pos.0 + next_direction.0, pos.1 + next_direction.1);
+            let next_cost = checked::add(cost, 1);
+
+            if !matches!(grid.at(next), Some('.' | 'E')) && next != shortcut {
+                continue;
+            }
+
+            if next_cost < *costs.entry(next).or_insert(isize::MAX) {
+                queue.push(next);
+                costs.insert(next, next_cost);
+            }
         }
-    } else {
-        shortcuts.insert((start, end));
     }
+    costs[&end]
 }
 
-fn dijkstra_continue(
+fn dijkstra_find_potential_shortcuts(
     grid: &Vec<Vec<char>>,
-    start: (isize, isize),
     end: (isize, isize),
     queue: &mut Vec<(isize, isize)>,
     costs: &mut HashMap<(isize, isize), isize>,
-    max_shortcut_reach: isize,
-    shortcuts_already_taken: &HashSet<Shortcut>,
-) -> HashSet<Shortcut> {
-    let mut previouses: HashMap<(isize, isize), (isize, isize)> = HashMap::new();
-    let mut shortcuts: HashSet<((isize, isize), (isize, isize))> = shortcuts_already_taken.clone();
-    let mut shortcuts_reach = HashMap::new();
-    shortcuts_reach.insert(start, max_shortcut_reach);
+    context: &Context,
+) -> (isize, Vec<(isize, isize)>) {
+    let mut potential_shortcuts = Vec::new();
 
     while let Some(pos) = queue.pop() {
+        context.step();
+
         let cost = costs[&pos];
-        let reach = shortcuts_reach[&pos];
 
         for next_direction in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
             let next = (pos.0 + next_direction.0, pos.1 + next_direction.1);
-            let next_cost = cost + 1;
 
-            let next_reach = match grid.at(next) {
-                None => continue,
-                Some('#') => reach - 1,
-                _ => reach,
-            };
-
-            if next_cost >= *costs.get(&next).unwrap_or(&isize::MAX) {
-                continue;
+            if matches!(grid.at(next), Some('#')) {
+                potential_shortcuts.push(next);
             }
-            if next_reach < 0 {
+
+            if !matches!(grid.at(next), Some('.' | 'E')) {
                 continue;
             }
 
-            previouses.insert(next, pos);
-
-            // if grid.at(pos) == Some('#') {
-            //     // shortcut_previouses.insert(next, pos);
-            // 
-            //     if grid.at(next) != Some('#') {
-            //         // let mut sh = HashSet::new();
-            //         // browse_previouses(next, next, &previouses, &mut sh);
-            //         // shortcuts.extend(sh);
-            // 
-            //         let mut current = next;
-            //         while let Some(&previous) = previouses.get(&current) {
-            //             // shortcuts.insert((previous, current));
-            //             current = previous;
-            //         }
-            // 
-            //         if shortcuts_already_taken.contains(&(current, next)) {
-            //             continue;
-            //         } else {
-            //             shortcuts.insert((current, next));
-            //         }
-            //     }
-            // }
-
-            queue.push(next);
-            costs.insert(next, next_cost);
-            shortcuts_reach.insert(next, next_reach);
-
-            queue.sort_by_key(|&pos| costs[&pos]);
-
-            // if let Some(&n) = shortcuts_reach.get(&next) {
-            //     if n <= next_reach {
-            //         shortcuts_reach.insert(next, next_reach);
-            //     }
-            // } else {
-            //     shortcuts_reach.insert(next, next_reach);
-            // }
+            let next_cost = checked::add(cost, 1);
+            if next_cost < *costs.entry(next).or_insert(isize::MAX) {
+                queue.push(next);
+                costs.insert(next, next_cost);
+            }
         }
     }
-
-    shortcuts
+    (costs[&end], potential_shortcuts)
 }
 
-// fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -> HashMap<isize, HashSet<(isize, isize)>> {
-//     let mut shortcuts: HashMap<isize, HashSet<_>> = HashMap::new();
-//
-//     let mut queue = Vec::new();
-//     let mut costs = HashMap::new();
-//
-//     costs.insert(start, 0);
-//     queue.push(start);
-//
-//     let (base_distance, potential_shortcuts) = dijkstra_find_potential_shortcuts(grid, end, &mut queue, &mut costs);
-//     assert!(queue.is_empty());
-//
-//     potential_shortcuts.iter().for_each(|&potential_shortcut| {
-//         // queue.push(start);
-//         let distance = dijkstra_with_shortcut(grid, start, end, potential_shortcut);
-//         // assert!(queue.is_empty());
-//
-//         shortcuts.entry(base_distance - distance).or_default().insert(potential_shortcut);
-//     });
-//
-//     shortcuts
-// }
-
-// fn dijkstra_with_shortcut(
-//     grid: &Vec<Vec<char>>,
-//     start: (isize, isize),
-//     end: (isize, isize),
-//     shortcut: (isize, isize),
-// ) -> isize {
-//     let mut queue = Vec::new();
-//     let mut costs = HashMap::new();
-//
-//     costs.insert(start, 0);
-//     queue.push(start);
-//
-//     while let Some(pos) = queue.pop() {
-//         let cost = costs[&pos];
-//
-//         for next_direction in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-//             let next = (pos.0 + next_direction.0, pos.1 + next_direction.1);
-//             let next_cost = cost + 1;
-//
-//             if !matches!(grid.at(next), Some('.' | 'E')) && next != shortcut {
-//                 continue;
-//             }
-//
-//             if next_cost < *costs.entry(next).or_insert(isize::MAX) {
-//                 queue.push(next);
-//                 costs.insert(next, next_cost);
-//             }
-//         }
-//     }
-//     costs[&end]
-// }
-
-// fn dijkstra_find_potential_shortcuts(
-//     grid: &Vec<Vec<char>>,
-//     end: (isize, isize),
-//     queue: &mut Vec<(isize, isize)>,
-//     costs: &mut HashMap<(isize, isize), isize>,
-// ) -> (isize, Vec<(isize, isize)>) {
-//     let mut potential_shortcuts = Vec::new();
-//
-//     while let Some(pos) = queue.pop() {
-//         let cost = costs[&pos];
-//
-//         for next_direction in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
-//             let next = (pos.0 + next_direction.0, pos.1 + next_direction.1);
-//
-//             if matches!(grid.at(next), Some('#')) {
-//                 potential_shortcuts.push(next);
-//             }
-//
-//             if !matches!(grid.at(next), Some('.' | 'E')) {
-//                 continue;
-//             }
-//
-//             let next_cost = cost + 1;
-//             if next_cost < *costs.entry(next).or_insert(isize::MAX) {
-//                 queue.push(next);
-//                 costs.insert(next, next_cost);
-//             }
-//         }
-//     }
-//     (costs[&end], potential_shortcuts)
-// }
-
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::grid(input);
+    problems.extend(validate::exactly_one(input, 'S'));
+    problems.extend(validate::exactly_one(input, 'E'));
+    problems
+}
 
-    let grid = input.lines()
+fn parse(input: &str) -> Vec<Vec<char>> {
+    input.lines()
         .map(|line| line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
+
+/// Cheats saving at least `min_saving` picoseconds, by racing again through each wall next to
+/// the track.
+fn cheats(grid: &Vec<Vec<char>>, context: &Context) -> usize {
+    let min_saving = context.param::<isize>("min_saving");
 
     let start = grid.grid_iter().find(|&(cell, _)| cell == 'S').unwrap().1;
     let end = grid.grid_iter().find(|&(cell, _)| cell == 'E').unwrap().1;
 
-    // let base_path = dijkstra_continue(&grid, end, &mut queue, &mut costs, 0);
-    // dbg!(base_path);
-
-    // {
-    //     let mut queue = Vec::new();
-    //     let mut costs = HashMap::new();
-    //
-    //     costs.insert(start, 0);
-    //     queue.push(start);
-    //
-    //     let (cost, shortcut) = dijkstra_continue(&grid, start, end, &mut queue, &mut costs, 1);
-    //
-    //     let mut queue = Vec::new();
-    //     let mut costs = HashMap::new();
-    //
-    //     costs.insert(start, 0);
-    //     queue.push(start);
-    //     costs.insert((6, 7), isize::MIN);
-    //
-    //     let a = dijkstra_continue(&grid, start, end, &mut queue, &mut costs, 1);
-    //     dbg!(a);
-    // }
-
-    // return;
-
-
-    // let base_distance = {
-    //     let mut queue = Vec::new();
-    //     let mut costs = HashMap::new();
-    //
-    //     costs.insert(start, 0);
-    //     queue.push(start);
-    //
-    //     let (base_distance, _) = dijkstra_continue(&grid, start, end, &mut queue, &mut costs, 0, &HashSet::new());
-    //     base_distance
-    // };
-
-    // let mut shortcut_set: HashSet<Shortcut> = HashSet::new();
-    // let mut shortcuts: HashMap<isize, Vec<Shortcut>> = HashMap::new();
+    checked::sum(dijkstra(grid, start, end, context).iter()
+        .filter(|&(&time_saved, _)| time_saved >= min_saving)
+        .map(|(_, cheats)| cheats.len()))
+}
 
-    let mut queue = Vec::new();
-    let mut costs = HashMap::new();
+/// The steps from `from` to every cell of the track, without cheating.
+fn distances(grid: &Vec<Vec<char>>, from: (isize, isize), context: &Context) -> HashMap<(isize, isize), isize> {
+    let mut distances = HashMap::from([(from, 0)]);
+    let mut queue = VecDeque::from([from]);
 
-    costs.insert(start, 0);
-    queue.push(start);
+    while let Some(pos) = queue.pop_front() {
+        context.step();
+
+        for direction in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+            let next = (pos.0 + direction.0, pos.1 + direction.1);
+
+            if matches!(grid.at(next), Some('.' | 'S' | 'E')) && !distances.contains_key(&next) {
+                distances.insert(next, checked::add(distances[&pos], 1));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// The same cheats as [`cheats`], timing the race through each wall from the distances of the
+/// track on either side of it to the start and to the end, instead of racing again.
+fn cheats_by_distances(grid: &Vec<Vec<char>>, context: &Context) -> usize {
+    let min_saving = context.param::<isize>("min_saving");
+    let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+    let start = grid.grid_iter().find(|&(cell, _)| cell == 'S').unwrap().1;
+    let end = grid.grid_iter().find(|&(cell, _)| cell == 'E').unwrap().1;
+
+    let from_start = distances(grid, start, context);
+    let from_end = distances(grid, end, context);
+    let base_distance = from_start[&end];
+
+    let walls = from_start.keys()
+        .flat_map(|&pos| directions.map(|direction| (pos.0 + direction.0, pos.1 + direction.1)))
+        .filter(|&next| grid.at(next) == Some('#'))
+        .collect::<HashSet<_>>();
+
+    walls.iter()
+        .filter(|&&wall| {
+            let sides = directions.map(|direction| (wall.0 + direction.0, wall.1 + direction.1));
+            let through_wall = sides.iter()
+                .flat_map(|before| sides.iter().map(move |after| (before, after)))
+                .filter(|(before, after)| before != after)
+                .filter_map(|(before, after)| Some(checked::add(checked::add(*from_start.get(before)?, 2), *from_end.get(after)?)))
+                .min()
+                .unwrap_or(base_distance);
+
+            checked::sub(base_distance, through_wall.min(base_distance)) >= min_saving
+        })
+        .count()
+}
 
-    // for &s in shortcut_set.iter() {
-    //     costs.insert(s, isize::MIN);
-    // }
-
-    let shortcuts = dijkstra_continue(&grid, start, end, &mut queue, &mut costs, 1, &HashSet::new());
-    let shortcuts = dijkstra_continue(&grid, start, end, &mut queue, &mut costs, 1, &shortcuts);
-    println!("len {:?}", shortcuts.len());
-
-    // for (cost, shortcut) in shortcuts.iter() {
-    //     println!("{}: {:?}", cost, shortcut);
-    // }
-    //
-    // let time_saved: usize = shortcuts.iter().filter_map(|(&time_saved, cheats)| {
-    //     if time_saved >= 100 {
-    //         Some(cheats.len())
-    //     } else {
-    //         None
-    //     }
-    // }).sum();
-    //
-    // println!("Part 1: {}", time_saved);
+fn main() {
+    Day::new("day_20", include_str!("input.txt"), validate, parse)
+        .param("min_saving", 100)
+        .part("Part 1", cheats)
+        .alternative("distances", cheats_by_distances)
+        .show_grid("the racetrack", |grid| grid)
+        .run();
 }
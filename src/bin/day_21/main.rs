@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::iter;
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
 
 trait Grid<T> {
@@ -47,19 +50,19 @@ impl<'a, T: Copy> Iterator for GridIterator<'a, T> {
     }
 }
 
-fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -> Vec<Vec<(isize, isize)>> {
-    if start == end {
-        return vec![];
-    }
-
-    let mut queue = Vec::new();
+/// Every shortest path from `start` to `end`, avoiding the gap.
+fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize), context: &Context) -> Vec<Vec<(isize, isize)>> {
+    let mut queue = VecDeque::new();
     let mut costs = HashMap::new();
     let mut previous: HashMap<(isize, isize), Vec<(isize, isize)>> = HashMap::new();
 
     costs.insert(start, 0);
-    queue.push(start);
+    queue.push_back(start);
+
+    // Every step costs the same, so positions leave the queue in order of cost.
+    while let Some(pos) = queue.pop_front() {
+        context.step();
 
-    while let Some(pos) = queue.pop() {
         let cost = costs[&pos];
 
         for next_direction in [(1, 0), (0, -1), (0, 1), (-1, 0)] {
@@ -69,77 +72,100 @@ fn dijkstra(grid: &Vec<Vec<char>>, start: (isize, isize), end: (isize, isize)) -
                 continue;
             }
 
-            let next_cost = cost + 1;
+            let next_cost = checked::add(cost, 1);
+            let known_cost = *costs.entry(next).or_insert_with(|| {
+                queue.push_back(next);
+                next_cost
+            });
 
-            if next_cost < *costs.entry(next).or_insert(isize::MAX)
-            {
-                queue.push(next);
-                costs.insert(next, next_cost);
+            if next_cost == known_cost {
                 previous.entry(next).or_default().push(pos);
             }
         }
     }
 
-    let mut path = Vec::new();
-    let mut p = end;
-    path.push(p);
-    while p != start {
-        p = previous[&p];
-        path.push(p);
-    }
-    path.reverse();
-    path
+    paths(&previous, start, end)
 }
 
-fn get_directional_code(keypad: &Vec<Vec<char>>, code: &Vec<char>, directional_keypad: bool) -> Vec<char> {
-    let mut directions = Vec::new();
-
-    code.iter().fold('A', |start, &end| {
-        let start_pos = keypad.grid_iter().find(|(cell, _)| *cell == start).unwrap().1;
-        let end_pos = keypad.grid_iter().find(|(cell, _)| *cell == end).unwrap().1;
-
-        let path = dijkstra(&keypad, start_pos, end_pos, directional_keypad);
-        let path = path.iter().tuple_windows().map(|(from, to)| {
-            (to.0 - from.0, to.1 - from.1)
-        }).collect::<Vec<_>>();
+/// The paths from `start` to `end` following the `previous` positions back.
+fn paths(previous: &HashMap<(isize, isize), Vec<(isize, isize)>>, start: (isize, isize), end: (isize, isize)) -> Vec<Vec<(isize, isize)>> {
+    if end == start {
+        return vec![vec![start]];
+    }
 
-        directions.push(path);
+    previous[&end].iter()
+        .flat_map(|&before| paths(previous, start, before))
+        .map(|mut path| {
+            path.push(end);
+            path
+        })
+        .collect()
+}
 
-        end
-    });
+/// The buttons to press on a directional keypad to move a robot arm along `path` and press the
+/// button at its end.
+fn get_directional_code(path: &[(isize, isize)]) -> Vec<char> {
+    let mut directional_code = path.iter().tuple_windows().map(|(from, to)| {
+        match (to.0 - from.0, to.1 - from.1) {
+            (1, 0) => '>',
+            (0, 1) => 'v',
+            (-1, 0) => '<',
+            (0, -1) => '^',
+            _ => unreachable!(),
+        }
+    }).collect::<Vec<_>>();
 
-    let mut directional_code = Vec::new();
+    directional_code.push('A');
+    directional_code
+}
 
-    for path in &directions {
-        for direction in path {
-            match direction {
-                (1, 0) => directional_code.push('>'),
-                (0, 1) => directional_code.push('v'),
-                (-1, 0) => directional_code.push('<'),
-                (0, -1) => directional_code.push('^'),
-                _ => unreachable!(),
+/// How many buttons to press on the last keypad to type `code` on the first of `keypads`, each one
+/// typed through the next.
+///
+/// Every robot arm is back on `A` once a button is pressed, so a move between two buttons costs the
+/// same wherever it happens and is kept in `moves` by the keypads left below.
+fn presses(keypads: &[&Vec<Vec<char>>], code: &[char], moves: &mut HashMap<(usize, char, char), usize>, context: &Context) -> usize {
+    let Some((keypad, below)) = keypads.split_first() else {
+        return code.len();
+    };
+
+    let mut total = 0;
+    let mut from = 'A';
+
+    for &to in code {
+        let cost = match moves.get(&(below.len(), from, to)) {
+            Some(&cost) => cost,
+            None => {
+                let start = keypad.grid_iter().find(|(cell, _)| *cell == from).unwrap().1;
+                let end = keypad.grid_iter().find(|(cell, _)| *cell == to).unwrap().1;
+
+                let cost = dijkstra(keypad, start, end, context).iter()
+                    .map(|path| presses(below, &get_directional_code(path), moves, context))
+                    .min()
+                    .unwrap();
+                moves.insert((below.len(), from, to), cost);
+                cost
             }
-        }
-        directional_code.push('A');
+        };
+
+        total = checked::add(total, cost);
+        from = to;
     }
-    println!("{}", directional_code.iter().collect::<String>());
-    println!("len {}", directional_code.iter().collect::<String>().len());
-    directional_code
+
+    total
 }
 
-fn code_complexity(code: &(&str, Vec<char>)) -> usize {
-    let numeric_part = code.0.chars()
+fn code_complexity(code: &str, presses: usize) -> usize {
+    let numeric_part = code.chars()
         .filter(char::is_ascii_digit)
         .collect::<String>()
         .parse::<usize>().unwrap();
 
-    println!("{}", code.1.iter().collect::<String>());
-    dbg!(numeric_part) * dbg!(code.1.len())
+    checked::mul(numeric_part, presses)
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-    let codes = input.lines();
+fn complexity(codes: &Vec<&str>, context: &Context) -> usize {
+    let keypads = context.param::<usize>("keypads");
 
     let numeric_keypad = vec![
         vec!['7', '8', '9'],
@@ -153,34 +179,34 @@ fn main() {
         vec!['<', 'v', '>'],
     ];
 
-    let codes = codes.map(|code| {
+    let keypads = iter::once(&numeric_keypad)
+        .chain(iter::repeat(&directional_keypad))
+        .take(keypads)
+        .collect::<Vec<_>>();
+    let mut moves = HashMap::new();
+
+    checked::sum(codes.iter().map(|&code| {
         let code_char_vec = code.chars().collect::<Vec<_>>();
+        code_complexity(code, presses(&keypads, &code_char_vec, &mut moves, context))
+    }))
+}
 
-        let (directional_code, _) = (0..3).fold((code_char_vec, &numeric_keypad), |(code, keypad), _| {
-            let directional_code = get_directional_code(keypad, &code, keypad == &directional_keypad);
-            (directional_code, &directional_keypad)
-        });
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected door codes".to_string()];
+    }
 
-        (code, directional_code)
-    }).collect::<Vec<_>>();
+    validate::lines(input, 1, |code| {
+        match code.strip_suffix('A') {
+            Some(digits) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => Ok(()),
+            _ => Err(format!("`{}` is not a door code, expected digits followed by A", code)),
+        }
+    })
+}
 
-    let complexity = codes.iter().map(code_complexity).sum::<usize>();
-
-    println!("Part 1: {}", complexity);
-
-    // let code = "<A>Av<<AA>^AA>AvAA^A<vAAA>^A";
-    // let mut pos = (2, 0);
-    // for c in code.chars() {
-    //     pos = match c {
-    //         '^' => (pos.0, pos.1 - 1),
-    //         'v' => (pos.0, pos.1 + 1),
-    //         '<' => (pos.0 - 1, pos.1),
-    //         '>' => (pos.0 + 1, pos.1),
-    //         'A' => {
-    //             print!("{}", numeric_keypad.at(pos).unwrap());
-    //             pos
-    //         }
-    //         _ => unreachable!(),
-    //     };
-    // }
+fn main() {
+    Day::new("day_21", include_str!("input.txt"), validate, |input| input.lines().collect())
+        .param("keypads", 3)
+        .part("Part 1", complexity)
+        .run();
 }
@@ -3,11 +3,13 @@
 use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::ops::BitXor;
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 
 fn next(n: usize) -> usize {
-    let n = n.bitxor(n * 64) % 16777216;
+    let n = n.bitxor(checked::mul(n, 64)) % 16777216;
     let n = n.bitxor(n >> 5) % 16777216;
-    n.bitxor(n * 2048) % 16777216
+    n.bitxor(checked::mul(n, 2048)) % 16777216
 }
 
 fn rand_iterator(seed: usize) -> impl Iterator<Item=usize> {
@@ -26,8 +28,8 @@ fn diff_sequence(prices: &[usize; 5]) -> [isize; 4] {
     ]
 }
 
-fn diff_to_price(seed: usize) -> HashMap<[isize; 4], usize> {
-    let secret_numbers = iter::once(seed).chain(rand_iterator(seed).take(2000));
+fn diff_to_price(seed: usize, iterations: usize) -> HashMap<[isize; 4], usize> {
+    let secret_numbers = iter::once(seed).chain(rand_iterator(seed).take(iterations));
 
     let diff_and_price = secret_numbers.map_windows(|five_prices| {
         let diff = diff_sequence(five_prices);
@@ -43,21 +45,36 @@ fn diff_to_price(seed: usize) -> HashMap<[isize; 4], usize> {
     diff_to_price
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected secret numbers".to_string()];
+    }
+
+    validate::lines(input, 1, |secret| {
+        secret.parse::<usize>()
+            .map(|_| ())
+            .map_err(|_| format!("`{}` is not a secret number", secret))
+    })
+}
 
-    let seeds = input.lines()
+fn parse(input: &str) -> Vec<usize> {
+    input.lines()
         .map(|line| line.parse::<usize>().unwrap())
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
+
+fn part_1(seeds: &Vec<usize>, context: &Context) -> usize {
+    let iterations = context.param::<usize>("iterations");
 
-    let sum: usize = seeds.iter()
-        .map(|&seed| rand_iterator(seed).take(2000).last().unwrap())
-        .sum();
+    checked::sum(seeds.iter()
+        .map(|&seed| rand_iterator(seed).take(iterations).last().unwrap_or(seed)))
+}
 
-    println!("Part 1: {}", sum);
+fn part_2(seeds: &Vec<usize>, context: &Context) -> usize {
+    let iterations = context.param::<usize>("iterations");
 
-    let seed_to_diff_to_price = seeds.iter().copied()
-        .map(diff_to_price)
+    let seed_to_diff_to_price = seeds.iter()
+        .map(|&seed| diff_to_price(seed, iterations))
         .collect::<Vec<_>>();
 
     let mut all_diffs = HashSet::new();
@@ -66,14 +83,20 @@ fn main() {
     }
 
     let diff_to_bananas = |diff| {
-        seed_to_diff_to_price.iter()
-            .map(|prices| prices.get(diff).unwrap_or(&0))
-            .sum::<usize>()
+        checked::sum(seed_to_diff_to_price.iter()
+            .map(|prices| *prices.get(diff).unwrap_or(&0)))
     };
 
-    let most_bananas = all_diffs.iter()
+    // Without four price changes, no sequence makes the monkeys sell.
+    all_diffs.iter()
         .map(diff_to_bananas)
-        .max().unwrap();
+        .max().unwrap_or(0)
+}
 
-    println!("Part 2: {:?}", most_bananas);
+fn main() {
+    Day::new("day_22", include_str!("input.txt"), validate, parse)
+        .param("iterations", 2000)
+        .part("Part 1", part_1)
+        .part("Part 2", part_2)
+        .run();
 }
@@ -1,3 +1,5 @@
+use advent_of_code_2024::validate;
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 
@@ -21,10 +23,27 @@ fn get_k_plus_1<'a>(k_graphs: &HashSet<Vec<&'a str>>, links: &HashMap<&str, Hash
     k_plus_1s
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+struct Network {
+    edges: HashSet<Vec<&'static str>>,
+    links: HashMap<&'static str, HashSet<&'static str>>,
+}
 
-    let mut k_graphs = HashSet::new();
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected connections".to_string()];
+    }
+
+    let computer = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase());
+    validate::lines(input, 1, |connection| {
+        match connection.split_once('-') {
+            Some((a, b)) if computer(a) && computer(b) => Ok(()),
+            _ => Err(format!("`{}` is not <computer>-<computer>", connection)),
+        }
+    })
+}
+
+fn parse(input: &'static str) -> Network {
+    let mut edges = HashSet::new();
     let mut links: HashMap<&str, HashSet<&str>> = HashMap::new();
 
     let connections = input.lines()
@@ -32,29 +51,63 @@ fn main() {
 
     for (a, b) in connections {
         let edge = [a, b].into_iter().sorted().collect::<Vec<_>>();
-        k_graphs.insert(edge);
+        edges.insert(edge);
         links.entry(a).or_default().insert(b);
         links.entry(b).or_default().insert(a);
     }
 
-    k_graphs = get_k_plus_1(&k_graphs, &links);
+    Network { edges, links }
+}
 
-    let k_three_starting_with_t = k_graphs.iter()
-        .filter(|mesh| mesh.len() == 3)
-        .filter(|mesh| mesh.iter().any(|&node| node.starts_with('t')))
-        .count();
+fn part_1(network: &Network, context: &Context) -> usize {
+    let prefix = context.param::<String>("prefix");
 
-    println!("Part 1: {}", k_three_starting_with_t);
+    let k_graphs = get_k_plus_1(&network.edges, &network.links);
 
+    k_graphs.iter()
+        .filter(|mesh| mesh.len() == 3)
+        .filter(|mesh| mesh.iter().any(|&node| node.starts_with(&prefix)))
+        .count()
+}
+
+fn biggest_k_graph<'a>(
+    mut k_graphs: HashSet<Vec<&'a str>>,
+    links: &HashMap<&str, HashSet<&'a str>>,
+    context: &Context,
+) -> Vec<&'a str> {
     loop {
-        let new_graphs = get_k_plus_1(&k_graphs, &links);
+        context.step();
+
+        let new_graphs = get_k_plus_1(&k_graphs, links);
         if new_graphs.is_empty() {
             break;
         }
         k_graphs = new_graphs;
     }
 
-    let biggest_k_graph = k_graphs.iter().next().unwrap();
+    k_graphs.into_iter().next().unwrap()
+}
+
+fn part_2(network: &Network, context: &Context) -> String {
+    biggest_k_graph(network.edges.clone(), &network.links, context).join(",")
+}
 
-    println!("Part 2: {}", biggest_k_graph.join(","));
+fn main() {
+    Day::new("day_23", include_str!("input.txt"), validate, parse)
+        .param("prefix", "t".to_string())
+        .part("Part 1", part_1)
+        .part("Part 2", part_2)
+        .command("clique", "clique containing <computer>: biggest LAN party the computer is part of", |network, context, args| {
+            match args {
+                ["containing", computer] => match network.links.get_key_value(computer) {
+                    Some((&computer, _)) => {
+                        let k_graph = biggest_k_graph(HashSet::from([vec![computer]]), &network.links, context);
+                        Ok(format!("{} ({} computers)", k_graph.join(","), k_graph.len()))
+                    }
+                    None => Err(format!("no computer named `{}`", computer)),
+                },
+                _ => Err("usage: clique containing <computer>".to_string()),
+            }
+        })
+        .run();
 }
@@ -0,0 +1,109 @@
+//! Runs the calls found by the lexer against a registry of instructions.
+
+use std::io::{self, ErrorKind, Read};
+use advent_of_code_2024::Context;
+use crate::lexer::{Call, Calls, Signature};
+
+/// What the instructions act on.
+pub struct State {
+    pub enabled: bool,
+    pub sum: u32,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sum: 0,
+        }
+    }
+}
+
+/// An instruction: how its calls are written and their effect on the state, given their arguments.
+pub struct Instruction {
+    pub signature: Signature,
+    pub effect: fn(&mut State, &[u32]),
+}
+
+/// The instructions recognized in memory, the other calls being corruption.
+pub struct Interpreter {
+    signatures: Vec<Signature>,
+    effects: Vec<fn(&mut State, &[u32])>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self {
+            signatures: Vec::new(),
+            effects: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, instruction: Instruction) -> Self {
+        self.signatures.push(instruction.signature);
+        self.effects.push(instruction.effect);
+        self
+    }
+
+    pub fn name(&self, call: &Call) -> &'static str {
+        self.signatures[call.instruction].name
+    }
+
+    pub fn calls<'a>(&'a self, memory: &'a [u8]) -> Calls<'a> {
+        Calls::new(&self.signatures, memory)
+    }
+
+    pub fn execute(&self, state: &mut State, call: &Call) {
+        (self.effects[call.instruction])(state, &call.args);
+    }
+
+    pub fn run(&self, memory: &[u8], context: &Context) -> State {
+        let mut state = State::default();
+
+        for call in self.calls(memory) {
+            context.step();
+            self.execute(&mut state, &call);
+        }
+
+        state
+    }
+
+    /// Runs memory read in chunks of `chunk_size` bytes, in constant memory.
+    ///
+    /// A call can only start where the longest one would still fit in what was read, the bytes
+    /// after that are kept for the next chunk so that calls straddling chunks are recognized.
+    pub fn run_stream(&self, mut reader: impl Read, chunk_size: usize, context: &Context) -> io::Result<State> {
+        let max_len = self.signatures.iter().map(Signature::max_len).max().unwrap_or(1);
+        let mut chunk = vec![0; chunk_size];
+        let mut buffer = Vec::with_capacity(chunk_size + max_len);
+        let mut state = State::default();
+
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(read) => read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            buffer.extend_from_slice(&chunk[..read]);
+
+            let limit = if read == 0 {
+                buffer.len()
+            } else {
+                (buffer.len() + 1).saturating_sub(max_len)
+            };
+
+            let mut calls = self.calls(&buffer).starting_before(limit);
+            for call in &mut calls {
+                context.step();
+                self.execute(&mut state, &call);
+            }
+
+            let position = calls.position();
+            buffer.drain(..position);
+
+            if read == 0 {
+                return Ok(state);
+            }
+        }
+    }
+}
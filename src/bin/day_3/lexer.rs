@@ -0,0 +1,118 @@
+//! Finds the well-formed calls like `mul(2,4)` in corrupted memory.
+
+use std::ops::Range;
+
+/// How a call to an instruction is written: its name and arguments of 1 to `max_digits` digits.
+pub struct Signature {
+    pub name: &'static str,
+    pub arity: usize,
+    pub max_digits: usize,
+}
+
+impl Signature {
+    /// Length of the longest well-formed call.
+    pub fn max_len(&self) -> usize {
+        self.name.len() + 2 + self.arity * self.max_digits + self.arity.saturating_sub(1)
+    }
+
+    /// Reads a call starting at `start`, returning its arguments and where it ends.
+    fn read(&self, memory: &[u8], start: usize) -> Option<(Vec<u32>, usize)> {
+        let mut position = start;
+
+        if !expect(memory, &mut position, self.name.as_bytes()) || !expect(memory, &mut position, b"(") {
+            return None;
+        }
+
+        let mut args = Vec::with_capacity(self.arity);
+        for arg in 0..self.arity {
+            if arg > 0 && !expect(memory, &mut position, b",") {
+                return None;
+            }
+
+            let digits = memory[position..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+            if digits == 0 || digits > self.max_digits {
+                return None;
+            }
+
+            let value = std::str::from_utf8(&memory[position..position + digits]).ok()?.parse().ok()?;
+            args.push(value);
+            position += digits;
+        }
+
+        expect(memory, &mut position, b")").then_some((args, position))
+    }
+}
+
+/// Skips over `bytes` if they come next.
+fn expect(memory: &[u8], position: &mut usize, bytes: &[u8]) -> bool {
+    let found = memory[*position..].starts_with(bytes);
+    if found {
+        *position += bytes.len();
+    }
+    found
+}
+
+/// A well-formed call to the instruction at index `instruction` of the signatures.
+pub struct Call {
+    pub instruction: usize,
+    pub args: Vec<u32>,
+    pub span: Range<usize>,
+}
+
+/// The call starting exactly at `start`, if any.
+pub fn call_at(signatures: &[Signature], memory: &[u8], start: usize) -> Option<Call> {
+    signatures.iter().enumerate().find_map(|(instruction, signature)| {
+        signature.read(memory, start).map(|(args, end)| Call {
+            instruction,
+            args,
+            span: start..end,
+        })
+    })
+}
+
+/// The calls of a memory dump in order, skipping over the corrupted bytes between them.
+pub struct Calls<'a> {
+    signatures: &'a [Signature],
+    memory: &'a [u8],
+    position: usize,
+    limit: usize,
+}
+
+impl<'a> Calls<'a> {
+    pub fn new(signatures: &'a [Signature], memory: &'a [u8]) -> Self {
+        Self {
+            signatures,
+            memory,
+            position: 0,
+            limit: memory.len(),
+        }
+    }
+
+    /// Only the calls starting before `limit`, for memory that may be cut short after it.
+    pub fn starting_before(self, limit: usize) -> Self {
+        Self { limit, ..self }
+    }
+
+    /// Where the search stopped: after the last call, or at the limit.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Iterator for Calls<'_> {
+    type Item = Call;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.limit {
+            match call_at(self.signatures, self.memory, self.position) {
+                Some(call) => {
+                    self.position = call.span.end;
+                    return Some(call);
+                }
+                None => self.position += 1,
+            }
+        }
+
+        None
+    }
+}
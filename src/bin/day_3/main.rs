@@ -1,31 +1,171 @@
-use regex::Regex;
+use std::io;
+use advent_of_code_2024::checked;
+use advent_of_code_2024::{Context, Day};
+use interpreter::{Instruction, Interpreter, State};
+use lexer::Signature;
+use serde_json::json;
 
-fn main() {
-    let input = include_str!("input.txt");
+mod interpreter;
+mod lexer;
+
+const MUL: Instruction = Instruction {
+    signature: Signature { name: "mul", arity: 2, max_digits: 3 },
+    effect: |state, args| if state.enabled {
+        state.sum = checked::add(state.sum, checked::mul(args[0], args[1]));
+    },
+};
 
-    let regex = Regex::new(r"mul\(([0-9]{1,3}),([0-9]{1,3})\)").unwrap();
+const DO: Instruction = Instruction {
+    signature: Signature { name: "do", arity: 0, max_digits: 0 },
+    effect: |state, _| state.enabled = true,
+};
 
-    let sum: u32 = regex.captures_iter(input).map(|captures| {
-        let terms: [u32; 2] = [1, 2].map(|i| captures.get(i).unwrap().as_str().parse().unwrap());
-        terms[0] * terms[1]
-    }).sum();
+const DONT: Instruction = Instruction {
+    signature: Signature { name: "don't", arity: 0, max_digits: 0 },
+    effect: |state, _| state.enabled = false,
+};
 
-    println!("Sum: {}", sum);
+const ADD: Instruction = Instruction {
+    signature: Signature { name: "add", arity: 2, max_digits: 3 },
+    effect: |state, args| if state.enabled {
+        state.sum = checked::add(state.sum, checked::add(args[0], args[1]));
+    },
+};
 
-    let regex = Regex::new(r"do\(\)|don't\(\)|mul\(([0-9]{1,3}),([0-9]{1,3})\)").unwrap();
+const TOGGLE: Instruction = Instruction {
+    signature: Signature { name: "toggle", arity: 0, max_digits: 0 },
+    effect: |state, _| state.enabled = !state.enabled,
+};
+
+/// Every instruction the `run` command can be given by name.
+fn instruction(name: &str) -> Option<Instruction> {
+    match name {
+        "mul" => Some(MUL),
+        "do" => Some(DO),
+        "don't" => Some(DONT),
+        "add" => Some(ADD),
+        "toggle" => Some(TOGGLE),
+        _ => None,
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+const CORRUPTED: &str = "\x1b[2m";
+const EXECUTED: &str = "\x1b[1;32m";
+const DISABLED: &str = "\x1b[31m";
+const SWITCH: &str = "\x1b[1;36m";
+
+fn conditional() -> Interpreter {
+    Interpreter::new()
+        .with(MUL)
+        .with(DO)
+        .with(DONT)
+}
 
-    let (_, sum) = regex.captures_iter(input).fold((true, 0), |(enabled, sum), captures| {
-        match captures.get(0).unwrap().as_str() {
-            "do()" => return (true, sum),
-            "don't()" => return (false, sum),
-            _ => if enabled {
-                let terms: [u32; 2] = [1, 2].map(|i| captures.get(i).unwrap().as_str().parse().unwrap());
-                (true, sum + terms[0] * terms[1])
-            } else {
-                (false, sum)
-            }
+/// The memory with its recognized calls highlighted and the products inline, or the calls as JSON
+/// with their byte spans.
+fn annotate(memory: &str, context: &Context, json: bool) -> String {
+    let interpreter = conditional();
+    let mut state = State::default();
+    let mut annotated = String::new();
+    let mut calls = Vec::new();
+    let mut position = 0;
+
+    for call in interpreter.calls(memory.as_bytes()) {
+        context.step();
+
+        let name = interpreter.name(&call);
+        let enabled = state.enabled;
+        let sum = state.sum;
+        interpreter.execute(&mut state, &call);
+        let contribution = state.sum - sum;
+
+        let color = match name {
+            "mul" if enabled => EXECUTED,
+            "mul" => DISABLED,
+            _ => SWITCH,
+        };
+        annotated += &format!("{}{}{}", CORRUPTED, &memory[position..call.span.start], RESET);
+        annotated += &format!("{}{}{}", color, &memory[call.span.clone()], RESET);
+        if name == "mul" && enabled {
+            annotated += &format!("{}[={}]{}", EXECUTED, contribution, RESET);
         }
-    });
+        position = call.span.end;
+
+        calls.push(json!({
+            "instruction": name,
+            "span": [call.span.start, call.span.end],
+            "args": call.args,
+            "enabled": enabled,
+            "contribution": contribution,
+        }));
+    }
+    annotated += &format!("{}{}{}", CORRUPTED, &memory[position..], RESET);
+
+    if json {
+        json!(calls).to_string()
+    } else {
+        annotated
+    }
+}
+
+fn sum(input: &&str, context: &Context) -> u32 {
+    Interpreter::new()
+        .with(MUL)
+        .run(input.as_bytes(), context)
+        .sum
+}
+
+fn conditional_sum(input: &&str, context: &Context) -> u32 {
+    conditional()
+        .run(input.as_bytes(), context)
+        .sum
+}
+
+fn interpreter(names: &[&str]) -> Result<Interpreter, String> {
+    names.iter().try_fold(Interpreter::new(), |interpreter, name| {
+        instruction(name)
+            .map(|instruction| interpreter.with(instruction))
+            .ok_or_else(|| format!("unknown instruction `{}`", name))
+    })
+}
+
+fn describe(State { enabled, sum }: State) -> String {
+    format!("sum {}, {} at the end", sum, if enabled { "enabled" } else { "disabled" })
+}
+
+/// Corrupted memory can hold anything, as long as there is some.
+fn validate(memory: &str) -> Vec<String> {
+    if memory.is_empty() {
+        vec!["the input is empty, expected corrupted memory".to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+fn main() {
+    Day::new("day_3", include_str!("input.txt"), validate, |input| input)
+        .param("chunk_size", 65536)
+        .part("Sum", sum)
+        .part("Conditional sum", conditional_sum)
+        .command("annotate", "annotate [json]: the memory with its instructions highlighted and the products inline, or as JSON", |input, context, args| match args {
+            [] => Ok(annotate(input, context, false)),
+            ["json"] => Ok(annotate(input, context, true)),
+            _ => Err("usage: annotate [json]".to_string()),
+        })
+        .command("run", "run <instruction>...: the sum with only these of mul, do, don't, add and toggle", |input, context, args| {
+            Ok(describe(interpreter(args)?.run(input.as_bytes(), context)))
+        })
+        .command("stream", "stream [<instruction>...]: the sum of the memory read from stdin in chunks, with mul, do and don't by default", |_, context, args| {
+            let interpreter = match args {
+                [] => conditional(),
+                names => interpreter(names)?,
+            };
 
-    println!("Conditional sum: {}", sum);
+            let chunk_size = context.param::<usize>("chunk_size").max(1);
+            interpreter.run_stream(io::stdin().lock(), chunk_size, context)
+                .map(describe)
+                .map_err(|e| format!("cannot read stdin: {}", e))
+        })
+        .run();
 }
@@ -1,5 +1,14 @@
 #![allow(non_snake_case)]
 
+use std::fs;
+use advent_of_code_2024::validate;
+use advent_of_code_2024::{render_grid, Context, Day};
+use itertools::Itertools;
+use template::Template;
+
+mod search;
+mod template;
+
 const XMAS_PATTERNS: [[(isize, isize); 3]; 8] = [
     [(1, 0), (2, 0), (3, 0)], [(-1, 0), (-2, 0), (-3, 0)],
     [(0, 1), (0, 2), (0, 3)], [(0, -1), (0, -2), (0, -3)],
@@ -14,6 +23,16 @@ const MAS_PATTERN: [[(isize, isize); 4]; 4] = [
     [(1, 1), (-1, -1), (1, -1), (-1, 1)],
 ];
 
+const RESET: &str = "\x1b[0m";
+const UNUSED: &str = "\x1b[2m";
+/// Colors of the cells used by 1, 2, 3, and 4 or more matches.
+const HEAT: [&str; 4] = ["\x1b[1;32m", "\x1b[1;33m", "\x1b[1;38;5;208m", "\x1b[1;31m"];
+
+const X_MAS: &str = "\
+M.S
+.A.
+M.S";
+
 trait Grid {
     fn at(&self, x: isize, y: isize) -> Option<char>;
     fn grid_iter(&self) -> GridIterator;
@@ -62,42 +81,161 @@ impl<'a> Iterator for GridIterator<'a> {
     }
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::grid(input);
+    problems.extend(validate::cells(input, "an uppercase letter", |c| c.is_ascii_uppercase()));
+    problems
+}
 
-    let grid = input
+fn parse(input: &str) -> Vec<Vec<char>> {
+    input
         .lines()
         .map(|line| line.chars()
             .collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+        .collect::<Vec<_>>()
+}
 
+fn xmas(grid: &Vec<Vec<char>>, _: &Context) -> usize {
+    search::find_words(grid, &["XMAS"]).len()
+}
+
+fn xmas_patterns(grid: &Vec<Vec<char>>, _: &Context) -> usize {
     let Xs = grid.grid_iter()
         .filter(|&(_, c)| c == 'X')
         .map(|((x, y), _)| (x as isize, y as isize));
 
-    let count = Xs.fold(0, |count, (x, y)| {
+    Xs.fold(0, |count, (x, y)| {
         let xmas_patterns = XMAS_PATTERNS.iter().filter(|pattern| {
             let letters = pattern.map(|(dx, dy)| grid.at(x + dx, y + dy));
             matches!(letters, [Some('M'), Some('A'), Some('S')])
         }).count();
 
         count + xmas_patterns
+    })
+}
+
+fn x_mas(grid: &Vec<Vec<char>>, _: &Context) -> usize {
+    let template = X_MAS.parse::<Template>().unwrap();
+
+    template.orientations().iter()
+        .map(|orientation| orientation.find(grid).len())
+        .sum()
+}
+
+/// Occurrences of a template, in every orientation with `rotations`, listing the orientations with
+/// their counts then the positions of their top-left corners.
+fn template(grid: &[Vec<char>], template: &Template, rotations: bool) -> String {
+    let orientations = if rotations {
+        template.orientations()
+    } else {
+        vec![template.clone()]
+    };
+
+    let matches = orientations.iter()
+        .map(|orientation| orientation.find(grid))
+        .collect::<Vec<_>>();
+
+    let mut lines = vec![format!(
+        "{} matches in {} orientations",
+        matches.iter().map(Vec::len).sum::<usize>(),
+        orientations.len(),
+    )];
+    for (index, (orientation, positions)) in orientations.iter().zip(&matches).enumerate() {
+        lines.push(format!("orientation {}: {} matches\n{}", index + 1, positions.len(), orientation));
+    }
+    for (index, positions) in matches.iter().enumerate() {
+        lines.extend(positions.iter().map(|(x, y)| format!("at {},{} in orientation {}", x, y, index + 1)));
+    }
+
+    lines.join("\n")
+}
+
+/// The grid with the cells used by no match dimmed, and the others colored by how many matches
+/// use them.
+fn render(grid: &[Vec<char>], cells: impl Iterator<Item=(usize, usize)>) -> String {
+    let mut uses = vec![vec![0; grid.first().map_or(0, Vec::len)]; grid.len()];
+    for (x, y) in cells {
+        uses[y][x] += 1;
+    }
+
+    let rows = grid.iter().zip(&uses).map(|(row, uses)| {
+        row.iter().zip(uses).map(|(&letter, &used)| {
+            let color = match used {
+                0 => UNUSED,
+                used => HEAT[used.min(HEAT.len()) - 1],
+            };
+            format!("{}{}{}", color, letter, RESET)
+        }).collect::<String>()
     });
 
-    println!("XMAS: {}", count);
+    let legend = HEAT.iter().enumerate()
+        .map(|(index, color)| format!("{}{}{}", color, index + 1, RESET))
+        .join(" ");
+
+    rows.chain([format!("matches per cell: {}+", legend)]).join("\n")
+}
 
+fn x_mas_patterns(grid: &Vec<Vec<char>>, _: &Context) -> usize {
     let As = grid.grid_iter()
         .filter(|&(_, c)| c == 'A')
         .map(|((x, y), _)| (x as isize, y as isize));
 
-    let count = As.fold(0, |count, (x, y)| {
+    As.fold(0, |count, (x, y)| {
         let mas_pattern = MAS_PATTERN.iter().filter(|pattern| {
             let letters = pattern.map(|(dx, dy)| grid.at(x + dx, y + dy));
             matches!(letters, [Some('M'), Some('S'), Some('M'), Some('S')])
         }).count();
 
         count + mas_pattern
-    });
+    })
+}
 
-    println!("X-MAS: {}", count);
+fn main() {
+    Day::new("day_4", include_str!("input.txt"), validate, parse)
+        .part("XMAS", xmas)
+        .alternative("patterns", xmas_patterns)
+        .part("X-MAS", x_mas)
+        .alternative("patterns", x_mas_patterns)
+        .command("show", "show grid|xmas|x-mas: print the word search, or highlight the cells of the XMAS or X-MAS matches", |grid, _, args| match args {
+            ["grid"] => Ok(render_grid(grid)),
+            ["xmas"] => {
+                let matches = search::find_words(grid, &["XMAS"]);
+                Ok(render(grid, matches.iter().flat_map(|found| found.cells())))
+            }
+            ["x-mas"] => {
+                let orientations = X_MAS.parse::<Template>().unwrap().orientations();
+                Ok(render(grid, orientations.iter().flat_map(|orientation| orientation.find(grid)
+                    .into_iter()
+                    .flat_map(move |position| orientation.cells(position)))))
+            }
+            _ => Err("usage: show grid|xmas|x-mas".to_string()),
+        })
+        .command("search", "search <word>...: how many times each word occurs, then where and in which direction", |grid, _, words| {
+            if words.is_empty() {
+                return Err("usage: search <word>...".to_string());
+            }
+
+            let matches = search::find_words(grid, words);
+            let counts = words.iter().enumerate().map(|(index, word)| {
+                format!("{}: {}", word, matches.iter().filter(|found| found.word == index).count())
+            });
+            let positions = matches.iter().map(|found| {
+                format!("{} at {},{} going {}", words[found.word], found.start.0, found.start.1, found.direction.name)
+            });
+
+            Ok(counts.chain(positions).join("\n"))
+        })
+        .command("template", "template <file> [rotations]: where the template of letters and . wildcards in the file occurs, rotated and reflected with `rotations`", |grid, _, args| {
+            let (path, rotations) = match args {
+                [path] => (path, false),
+                [path, "rotations"] => (path, true),
+                _ => return Err("usage: template <file> [rotations]".to_string()),
+            };
+
+            let text = fs::read_to_string(path).map_err(|e| format!("cannot read `{}`: {}", path, e))?;
+            let parsed = text.parse::<Template>().map_err(|e| format!("{}: {}", path, e))?;
+
+            Ok(template(grid, &parsed, rotations))
+        })
+        .run();
 }
@@ -0,0 +1,89 @@
+//! Finds words in a grid of letters, in the eight directions and overlapping each other.
+
+/// A direction to read a word in, `step` being how `(x, y)` changes from a letter to the next.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Direction {
+    pub name: &'static str,
+    pub step: (isize, isize),
+}
+
+/// Every direction, each one followed three directions later by its opposite.
+pub const DIRECTIONS: [Direction; 8] = [
+    Direction { name: "E", step: (1, 0) },
+    Direction { name: "SE", step: (1, 1) },
+    Direction { name: "S", step: (0, 1) },
+    Direction { name: "SW", step: (-1, 1) },
+    Direction { name: "W", step: (-1, 0) },
+    Direction { name: "NW", step: (-1, -1) },
+    Direction { name: "N", step: (0, -1) },
+    Direction { name: "NE", step: (1, -1) },
+];
+
+/// The letter at `(x, y)`, `x` being the column.
+pub fn letter(grid: &[Vec<char>], x: isize, y: isize) -> Option<char> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    grid.get(y as usize).and_then(|row| row.get(x as usize).copied())
+}
+
+/// An occurrence of the word at index `word`.
+pub struct WordMatch {
+    pub word: usize,
+    pub start: (usize, usize),
+    pub direction: Direction,
+    pub len: usize,
+}
+
+impl WordMatch {
+    pub fn cells(&self) -> impl Iterator<Item=(usize, usize)> + '_ {
+        (0..self.len as isize).map(|i| (
+            (self.start.0 as isize + i * self.direction.step.0) as usize,
+            (self.start.1 as isize + i * self.direction.step.1) as usize,
+        ))
+    }
+}
+
+/// Every occurrence of the words.
+///
+/// A palindrome reads the same from both of its ends, so it is only looked for in the four
+/// directions that are not opposite of each other, and a single letter in one direction.
+pub fn find_words(grid: &[Vec<char>], words: &[&str]) -> Vec<WordMatch> {
+    let mut matches = Vec::new();
+
+    for (index, word) in words.iter().enumerate() {
+        let letters = word.chars().collect::<Vec<_>>();
+        let directions = match letters.len() {
+            0 => continue,
+            1 => &DIRECTIONS[..1],
+            _ if letters.iter().eq(letters.iter().rev()) => &DIRECTIONS[..4],
+            _ => &DIRECTIONS[..],
+        };
+
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell != letters[0] {
+                    continue;
+                }
+
+                for &direction in directions {
+                    let found = letters.iter().enumerate().all(|(i, &expected)| {
+                        let (dx, dy) = direction.step;
+                        letter(grid, x as isize + i as isize * dx, y as isize + i as isize * dy) == Some(expected)
+                    });
+
+                    if found {
+                        matches.push(WordMatch {
+                            word: index,
+                            start: (x, y),
+                            direction,
+                            len: letters.len(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    matches
+}
@@ -0,0 +1,107 @@
+//! Finds 2D templates of letters and `.` wildcards in a grid, optionally rotated and reflected.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use crate::search::letter;
+
+/// The letters of a template by their offset from its top-left corner, wildcards left out.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Template {
+    width: usize,
+    height: usize,
+    letters: Vec<((usize, usize), char)>,
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>();
+
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let letters = rows.iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.chars()
+                .enumerate()
+                .filter(|&(_, c)| c != '.')
+                .map(move |(x, c)| ((x, y), c)))
+            .collect::<Vec<_>>();
+
+        if letters.is_empty() {
+            return Err("the template has no letters".to_string());
+        }
+
+        Ok(Template::new(width, rows.len(), letters))
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = vec![vec!['.'; self.width]; self.height];
+        for &((x, y), c) in &self.letters {
+            rows[y][x] = c;
+        }
+
+        let rows = rows.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+impl Template {
+    /// Sorts the letters, so that equal templates compare equal.
+    fn new(width: usize, height: usize, mut letters: Vec<((usize, usize), char)>) -> Self {
+        letters.sort();
+        Self { width, height, letters }
+    }
+
+    /// A quarter turn clockwise.
+    fn rotated(&self) -> Self {
+        let letters = self.letters.iter()
+            .map(|&((x, y), c)| ((self.height - 1 - y, x), c))
+            .collect();
+        Template::new(self.height, self.width, letters)
+    }
+
+    /// Mirrored left to right.
+    fn reflected(&self) -> Self {
+        let letters = self.letters.iter()
+            .map(|&((x, y), c)| ((self.width - 1 - x, y), c))
+            .collect();
+        Template::new(self.width, self.height, letters)
+    }
+
+    /// The distinct rotations and reflections of the template, itself first. A symmetric template
+    /// has fewer than eight of them.
+    pub fn orientations(&self) -> Vec<Template> {
+        let mut orientations: Vec<Template> = Vec::new();
+
+        for mut orientation in [self.clone(), self.reflected()] {
+            for _ in 0..4 {
+                if !orientations.contains(&orientation) {
+                    orientations.push(orientation.clone());
+                }
+                orientation = orientation.rotated();
+            }
+        }
+
+        orientations
+    }
+
+    /// The cells of the template placed with its top-left corner at `position`.
+    pub fn cells(&self, (x, y): (usize, usize)) -> impl Iterator<Item=(usize, usize)> + '_ {
+        self.letters.iter().map(move |&((dx, dy), _)| (x + dx, y + dy))
+    }
+
+    /// The positions of the top-left corner where the grid matches the template.
+    pub fn find(&self, grid: &[Vec<char>]) -> Vec<(usize, usize)> {
+        let rows = grid.len();
+        let columns = grid.first().map_or(0, Vec::len);
+
+        (0..(rows + 1).saturating_sub(self.height))
+            .flat_map(|y| (0..(columns + 1).saturating_sub(self.width)).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.letters.iter().all(|&((dx, dy), c)| {
+                letter(grid, (x + dx) as isize, (y + dy) as isize) == Some(c)
+            }))
+            .collect()
+    }
+}
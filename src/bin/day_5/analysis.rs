@@ -0,0 +1,109 @@
+//! Audits the rules as a whole, independently of whether the updates follow them.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use itertools::Itertools;
+
+/// Pages that each must come before themselves through the rules.
+pub struct Cycle {
+    pub pages: Vec<usize>,
+    /// The rules between the pages of the cycle.
+    pub rules: Vec<(usize, usize)>,
+}
+
+pub struct Analysis {
+    /// The cycles, by smallest page.
+    pub cycles: Vec<Cycle>,
+    /// Rules `before|after` between pages of different cycles, or outside of any, that other such
+    /// rules already imply.
+    pub redundant: Vec<(usize, usize)>,
+    /// Pages in the updates that no rule mentions.
+    pub unruled: Vec<usize>,
+    /// Pairs of pages sharing an update with no rule between them, either way.
+    pub unordered: Vec<(usize, usize)>,
+}
+
+/// The pages reachable from `page` through the rules, not including itself unless in a cycle.
+fn reachable(precedence: &HashMap<usize, HashSet<usize>>, page: usize) -> HashSet<usize> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![page];
+
+    while let Some(page) = stack.pop() {
+        for &after in precedence.get(&page).into_iter().flatten() {
+            if seen.insert(after) {
+                stack.push(after);
+            }
+        }
+    }
+
+    seen
+}
+
+fn ordered(precedence: &HashMap<usize, HashSet<usize>>, a: usize, b: usize) -> bool {
+    precedence.get(&a).is_some_and(|set| set.contains(&b))
+}
+
+pub fn analyze(precedence: &HashMap<usize, HashSet<usize>>, updates: &[Vec<usize>]) -> Analysis {
+    let pages = precedence.iter()
+        .flat_map(|(&before, afters)| afters.iter().copied().chain([before]))
+        .collect::<BTreeSet<_>>();
+    let reach = pages.iter()
+        .map(|&page| (page, reachable(precedence, page)))
+        .collect::<HashMap<_, _>>();
+
+    let rules = pages.iter()
+        .flat_map(|&before| precedence.get(&before).into_iter().flatten().map(move |&after| (before, after)))
+        .sorted()
+        .collect::<Vec<_>>();
+
+    // The strongly connected components with a cycle through them, by which each page is known in
+    // the condensation of the rules. Pages outside of any cycle are their own component.
+    let mut cycles = Vec::new();
+    let mut component = HashMap::new();
+    for &page in &pages {
+        if component.contains_key(&page) || !reach[&page].contains(&page) {
+            continue;
+        }
+        let cycle = pages.iter()
+            .copied()
+            .filter(|other| reach[&page].contains(other) && reach[other].contains(&page))
+            .collect::<Vec<_>>();
+        component.extend(cycle.iter().map(|&other| (other, page)));
+        let rules = rules.iter()
+            .copied()
+            .filter(|(before, after)| cycle.contains(before) && cycle.contains(after))
+            .collect();
+        cycles.push(Cycle { pages: cycle, rules });
+    }
+    let component = |page: usize| component.get(&page).copied().unwrap_or(page);
+
+    // Within a cycle every rule is implied by the others, so only the rules of the condensation
+    // count, and one is redundant when another component after its first page reaches its second.
+    let redundant = rules.iter()
+        .copied()
+        .filter(|&(before, after)| component(before) != component(after))
+        .filter(|&(before, after)| rules.iter().any(|&(from, through)| {
+            component(from) == component(before)
+                && component(through) != component(before)
+                && component(through) != component(after)
+                && reach[&through].contains(&after)
+        }))
+        .collect();
+
+    let unruled = updates.iter()
+        .flatten()
+        .copied()
+        .filter(|page| !pages.contains(page))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let unordered = updates.iter()
+        .flat_map(|update| update.iter().copied().tuple_combinations())
+        .filter(|&(a, b)| a != b && !ordered(precedence, a, b) && !ordered(precedence, b, a))
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Analysis { cycles, redundant, unruled, unordered }
+}
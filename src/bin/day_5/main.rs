@@ -1,8 +1,20 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
+use itertools::Itertools;
+use serde_json::json;
 
-fn main() {
-    let input = include_str!("input.txt");
+mod analysis;
+mod order;
+mod violations;
+
+struct Manual {
+    precedence: HashMap<usize, HashSet<usize>>,
+    updates: Vec<Vec<usize>>,
+}
+
+fn parse(input: &str) -> Manual {
     let (rules, updates) = input.split_once("\n\n").unwrap();
 
     let mut precedence: HashMap<usize, HashSet<usize>> = HashMap::new();
@@ -19,24 +31,70 @@ fn main() {
         ).collect::<Vec<_>>()
     ).collect::<Vec<_>>();
 
-    let (correct_updates, mut incorrect_updates): (Vec<_>, Vec<_>) = updates.into_iter().partition(|update|
+    Manual { precedence, updates }
+}
+
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::blank_lines(input, 1);
+
+    let Some((rules, updates)) = input.split_once("\n\n") else {
+        return problems;
+    };
+
+    problems.extend(validate::lines(rules, 1, |rule| {
+        let (left, right) = rule.split_once('|').ok_or(format!("rule `{}` is not <page>|<page>", rule))?;
+        left.parse::<usize>().and(right.parse::<usize>())
+            .map(|_| ())
+            .map_err(|_| format!("rule `{}` is not <page>|<page>", rule))
+    }));
+    problems.extend(validate::lines(updates, rules.lines().count() + 2, |update| {
+        let pages = update.split(',')
+            .map(|page| page.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("update `{}` is not a list of pages separated by commas", update))?;
+
+        match pages.iter().duplicates().next() {
+            Some(page) => Err(format!("update `{}` has page {} more than once", update, page)),
+            None => Ok(()),
+        }
+    }));
+
+    problems
+}
+
+fn partition(manual: &Manual) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    manual.updates.iter().cloned().partition(|update|
         update.is_sorted_by(|a, b|
-            precedence
+            manual.precedence
                 .get(a)
                 .and_then(|set| set.get(b))
                 .is_some()
         )
-    );
+    )
+}
+
+fn correct(manual: &Manual, _: &Context) -> usize {
+    let (correct_updates, _) = partition(manual);
+
+    checked::sum(correct_updates.iter()
+        .map(|update| update[update.len() / 2]))
+}
+
+fn incorrect(manual: &Manual, context: &Context) -> usize {
+    let (_, incorrect_updates) = partition(manual);
 
-    let sum: usize = correct_updates.iter()
-        .map(|update| update[update.len() / 2])
-        .sum();
+    checked::sum(incorrect_updates.iter()
+        .map(|update| order::middle(&manual.precedence, update).unwrap_or_else(|e| {
+            context.fail(format!("no middle page for {}: {}", update.iter().join(","), e))
+        })))
+}
 
-    println!("Correct: {}", sum);
+fn incorrect_comparator(manual: &Manual, _: &Context) -> usize {
+    let (_, mut incorrect_updates) = partition(manual);
 
     for update in incorrect_updates.iter_mut() {
         update.sort_by(|a, b| {
-            let smaller = precedence
+            let smaller = manual.precedence
                 .get(a)
                 .and_then(|set| set.get(b))
                 .is_some();
@@ -49,9 +107,114 @@ fn main() {
         });
     }
 
-    let sum: usize = incorrect_updates.iter()
-        .map(|update| update[update.len() / 2])
-        .sum();
+    checked::sum(incorrect_updates.iter()
+        .map(|update| update[update.len() / 2]))
+}
+
+/// The rules each incorrect update breaks and the pages to move to fix it, as text or JSON.
+fn explain(manual: &Manual, json: bool) -> String {
+    let incorrect_updates = manual.updates.iter()
+        .enumerate()
+        .filter(|(_, update)| !violations::violations(&manual.precedence, update).is_empty());
+
+    let mut explanations = Vec::new();
+    let mut lines = Vec::new();
+
+    for (index, update) in incorrect_updates {
+        let violations = violations::violations(&manual.precedence, update);
+        let to_move = violations::pages_to_move(&manual.precedence, update);
+
+        lines.push(format!("update {}: {}", index + 1, update.iter().join(",")));
+        for violation in &violations {
+            lines.push(format!(
+                "    breaks {}|{}: {} at {} comes after {} at {}",
+                violation.before.0, violation.after.0,
+                violation.before.0, violation.before.1,
+                violation.after.0, violation.after.1,
+            ));
+        }
+        lines.push(match &to_move {
+            Ok(pages) => format!("    move {}", pages.iter().join(", ")),
+            Err(e) => format!("    cannot be fixed: {}", e),
+        });
+
+        explanations.push(json!({
+            "update": index + 1,
+            "pages": update,
+            "violations": violations.iter().map(|violation| json!({
+                "rule": [violation.before.0, violation.after.0],
+                "positions": [violation.before.1, violation.after.1],
+            })).collect::<Vec<_>>(),
+            "move": to_move.as_ref().ok(),
+            "error": to_move.as_ref().err().map(|e| e.to_string()),
+        }));
+    }
+
+    if json {
+        json!(explanations).to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+/// What the rules get wrong or leave out, a line per finding.
+fn audit(manual: &Manual) -> String {
+    let analysis = analysis::analyze(&manual.precedence, &manual.updates);
+    let mut lines = Vec::new();
+
+    lines.push(format!("{} cycles", analysis.cycles.len()));
+    for cycle in &analysis.cycles {
+        lines.push(format!("    {} pages: {}", cycle.pages.len(), cycle.pages.iter().join(",")));
+        lines.push(format!("        {} rules: {}", cycle.rules.len(), cycle.rules.iter().map(|(a, b)| format!("{}|{}", a, b)).join(" ")));
+    }
+
+    lines.push(format!("{} redundant rules", analysis.redundant.len()));
+    if !analysis.redundant.is_empty() {
+        lines.push(format!("    {}", analysis.redundant.iter().map(|(a, b)| format!("{}|{}", a, b)).join(" ")));
+    }
+
+    lines.push(format!("{} pages in no rule", analysis.unruled.len()));
+    if !analysis.unruled.is_empty() {
+        lines.push(format!("    {}", analysis.unruled.iter().join(",")));
+    }
+
+    lines.push(format!("{} pairs of pages in an update without a rule", analysis.unordered.len()));
+    if !analysis.unordered.is_empty() {
+        lines.push(format!("    {}", analysis.unordered.iter().map(|(a, b)| format!("{} and {}", a, b)).join(", ")));
+    }
+
+    lines.join("\n")
+}
+
+fn main() {
+    Day::new("day_5", include_str!("input.txt"), validate, parse)
+        .part("Correct", correct)
+        .part("Incorrect", incorrect)
+        .alternative("comparator", incorrect_comparator)
+        .command("reorder", "reorder: each incorrect update in the order of its rules, or why it has none", |manual, _, args| {
+            if !args.is_empty() {
+                return Err("usage: reorder".to_string());
+            }
+
+            let (_, incorrect_updates) = partition(manual);
+            Ok(incorrect_updates.iter()
+                .map(|update| match order::reorder(&manual.precedence, update) {
+                    Ok(reordered) => format!("{} -> {}", update.iter().join(","), reordered.iter().join(",")),
+                    Err(e) => format!("{}: {}", update.iter().join(","), e),
+                })
+                .join("\n"))
+        })
+        .command("violations", "violations [json]: the rules each incorrect update breaks and the fewest pages to move to fix it", |manual, _, args| match args {
+            [] => Ok(explain(manual, false)),
+            ["json"] => Ok(explain(manual, true)),
+            _ => Err("usage: violations [json]".to_string()),
+        })
+        .command("analyze", "analyze: cycles, redundant rules, pages without rules and pairs of pages in an update without one", |manual, _, args| {
+            if !args.is_empty() {
+                return Err("usage: analyze".to_string());
+            }
 
-    println!("Incorrect: {}", sum);
+            Ok(audit(manual))
+        })
+        .run();
 }
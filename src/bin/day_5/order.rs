@@ -0,0 +1,166 @@
+//! Orders the pages of an update by the rules between them.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use itertools::Itertools;
+
+/// Why the rules between the pages of an update do not give a single order.
+pub enum Unorderable {
+    /// Pages each of which must come before the next, and the last before the first.
+    Cycle(Vec<usize>),
+    /// Pairs of pages that no rule orders, even through other pages.
+    Ambiguous(Vec<(usize, usize)>),
+}
+
+impl Display for Unorderable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unorderable::Cycle(cycle) => write!(
+                f,
+                "the rules form a cycle {} -> {}",
+                cycle.iter().join(" -> "),
+                cycle[0],
+            ),
+            Unorderable::Ambiguous(pairs) => write!(
+                f,
+                "no rule orders {}",
+                pairs.iter().map(|(a, b)| format!("{} and {}", a, b)).join(", "),
+            ),
+        }
+    }
+}
+
+/// The rules restricted to the pages of an update: the pages that must come after each page.
+fn restricted(precedence: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> HashMap<usize, Vec<usize>> {
+    update.iter()
+        .map(|&page| {
+            let after = update.iter()
+                .copied()
+                .filter(|after| *after != page && precedence.get(&page).is_some_and(|set| set.contains(after)))
+                .collect();
+            (page, after)
+        })
+        .collect()
+}
+
+/// Walks back from pages left with predecessors once the others are ordered. As each of them
+/// still has a predecessor among them, the walk ends up going around a cycle.
+fn find_cycle(successors: &HashMap<usize, Vec<usize>>, left: &HashSet<usize>) -> Vec<usize> {
+    let predecessor = |page: usize| *left.iter()
+        .find(|before| successors[before].contains(&page))
+        .unwrap();
+
+    let mut walk = vec![*left.iter().next().unwrap()];
+    loop {
+        let before = predecessor(*walk.last().unwrap());
+        if let Some(start) = walk.iter().position(|&page| page == before) {
+            let mut cycle = walk.split_off(start);
+            cycle.reverse();
+            return cycle;
+        }
+        walk.push(before);
+    }
+}
+
+/// The pages reachable from `page` through the rules.
+fn descendants(successors: &HashMap<usize, Vec<usize>>, page: usize) -> HashSet<usize> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![page];
+
+    while let Some(page) = stack.pop() {
+        for &after in &successors[&page] {
+            if seen.insert(after) {
+                stack.push(after);
+            }
+        }
+    }
+
+    seen
+}
+
+/// Sorts the pages of an update topologically, telling whether other orders were possible.
+fn topological(successors: &HashMap<usize, Vec<usize>>, update: &[usize]) -> Result<(Vec<usize>, bool), Unorderable> {
+    let mut predecessors: HashMap<usize, usize> = update.iter().map(|&page| (page, 0)).collect();
+    for after in successors.values().flatten() {
+        *predecessors.get_mut(after).unwrap() += 1;
+    }
+
+    let mut ready = update.iter().copied().filter(|page| predecessors[page] == 0).collect::<Vec<_>>();
+    let mut ambiguous = false;
+    let mut order = Vec::with_capacity(update.len());
+
+    while !ready.is_empty() {
+        ambiguous |= ready.len() > 1;
+
+        let page = ready.remove(0);
+        order.push(page);
+
+        for after in &successors[&page] {
+            let count = predecessors.get_mut(after).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.push(*after);
+            }
+        }
+    }
+
+    if order.len() < update.len() {
+        let left = update.iter().copied().filter(|page| !order.contains(page)).collect();
+        return Err(Unorderable::Cycle(find_cycle(successors, &left)));
+    }
+
+    Ok((order, ambiguous))
+}
+
+/// Sorts the pages of an update topologically, using only the rules between them.
+pub fn reorder(precedence: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> Result<Vec<usize>, Unorderable> {
+    let successors = restricted(precedence, update);
+    let (order, ambiguous) = topological(&successors, update)?;
+
+    if ambiguous {
+        let reachable = update.iter()
+            .map(|&page| (page, descendants(&successors, page)))
+            .collect::<HashMap<_, _>>();
+
+        let unordered = order.iter()
+            .tuple_combinations()
+            .filter(|(a, b)| !reachable[a].contains(b) && !reachable[b].contains(a))
+            .map(|(&a, &b)| (a, b))
+            .collect();
+
+        return Err(Unorderable::Ambiguous(unordered));
+    }
+
+    Ok(order)
+}
+
+/// The pages that must come after each page of the update, through the rules between them.
+pub fn closure(precedence: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> Result<HashMap<usize, HashSet<usize>>, Unorderable> {
+    let successors = restricted(precedence, update);
+    topological(&successors, update)?;
+
+    Ok(update.iter()
+        .map(|&page| (page, descendants(&successors, page)))
+        .collect())
+}
+
+/// The middle page of the update in the order of its rules, which needs only that page to be
+/// ordered against all the others: then it has the same place in every order.
+pub fn middle(precedence: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> Result<usize, Unorderable> {
+    let successors = restricted(precedence, update);
+    let (order, _) = topological(&successors, update)?;
+    let middle = order[order.len() / 2];
+
+    let after = descendants(&successors, middle);
+    let unordered = order.iter()
+        .copied()
+        .filter(|&page| page != middle && !after.contains(&page) && !descendants(&successors, page).contains(&middle))
+        .map(|page| (middle.min(page), middle.max(page)))
+        .collect::<Vec<_>>();
+
+    if unordered.is_empty() {
+        Ok(middle)
+    } else {
+        Err(Unorderable::Ambiguous(unordered))
+    }
+}
@@ -0,0 +1,86 @@
+//! Explains what is wrong with an incorrect update.
+
+use std::collections::{HashMap, HashSet};
+use crate::order::{self, Unorderable};
+
+/// A rule `before|after` broken by an update, with the positions of its pages in the update.
+pub struct Violation {
+    pub before: (usize, usize),
+    pub after: (usize, usize),
+}
+
+/// The rules broken by the update: those whose `after` page comes first.
+pub fn violations(precedence: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (i, &first) in update.iter().enumerate() {
+        for (j, &second) in update.iter().enumerate().skip(i + 1) {
+            if precedence.get(&second).is_some_and(|set| set.contains(&first)) {
+                violations.push(Violation {
+                    before: (second, j),
+                    after: (first, i),
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Looks for a page left of `left` can be matched to, moving earlier matches along if need be.
+fn augment(inversions: &[Vec<usize>], left: usize, matched: &mut [Option<usize>], seen: &mut [bool]) -> bool {
+    for &right in &inversions[left] {
+        if !seen[right] {
+            seen[right] = true;
+            if matched[right].is_none_or(|other| augment(inversions, other, matched, seen)) {
+                matched[right] = Some(left);
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// The fewest pages to move for the update to follow its rules, the others keeping their order.
+///
+/// The pages that stay must have no pair in the wrong order, even through other pages. Being in
+/// the wrong order relative to a later page is transitive, so they are a largest antichain of it,
+/// found from a maximum matching as in Dilworth's theorem.
+pub fn pages_to_move(precedence: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> Result<Vec<usize>, Unorderable> {
+    let closure = order::closure(precedence, update)?;
+
+    // For each position, the later positions whose page must come before.
+    let inversions = (0..update.len())
+        .map(|i| (i + 1..update.len())
+            .filter(|&j| closure[&update[j]].contains(&update[i]))
+            .collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let mut matched = vec![None; update.len()];
+    let has_match = (0..update.len())
+        .map(|left| augment(&inversions, left, &mut matched, &mut vec![false; update.len()]))
+        .collect::<Vec<_>>();
+
+    // Alternating paths from the unmatched positions, as in König's theorem: a position stays if
+    // they reach it as a left end but not as a right one.
+    let mut reached_left = vec![false; update.len()];
+    let mut reached_right = vec![false; update.len()];
+    let mut stack = (0..update.len()).filter(|&left| !has_match[left]).collect::<Vec<_>>();
+    while let Some(left) = stack.pop() {
+        if std::mem::replace(&mut reached_left[left], true) {
+            continue;
+        }
+        for &right in &inversions[left] {
+            if !std::mem::replace(&mut reached_right[right], true) {
+                stack.extend(matched[right]);
+            }
+        }
+    }
+
+    Ok(update.iter()
+        .enumerate()
+        .filter(|&(i, _)| !reached_left[i] || reached_right[i])
+        .map(|(_, &page)| page)
+        .collect())
+}
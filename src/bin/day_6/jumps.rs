@@ -0,0 +1,96 @@
+//! Moves the guard from turn to turn, looking up the next obstacle ahead instead of walking to it.
+
+use std::collections::HashMap;
+use advent_of_code_2024::Context;
+use crate::Position;
+
+/// North, east, south and west, each a right turn from the previous one.
+pub const DIRECTIONS: [Position; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+pub struct Jumps {
+    width: isize,
+    height: isize,
+    /// For each cell and direction, the cell the guard stops at in front of the next obstacle, or
+    /// `None` if it walks off the map first.
+    stops: Vec<[Option<Position>; 4]>,
+}
+
+impl Jumps {
+    pub fn new(grid: &[Vec<char>]) -> Self {
+        let height = grid.len() as isize;
+        let width = grid.first().map_or(0, Vec::len) as isize;
+        let mut jumps = Self { width, height, stops: vec![[None; 4]; (width * height) as usize] };
+
+        for (direction, &(dx, dy)) in DIRECTIONS.iter().enumerate() {
+            // The cells ahead first, so that each stop is the one of the next cell when it is free.
+            let xs = (0..width).collect::<Vec<_>>();
+            let ys = (0..height).collect::<Vec<_>>();
+            let xs = if dx > 0 { xs.into_iter().rev().collect() } else { xs };
+            let ys = if dy > 0 { ys.into_iter().rev().collect() } else { ys };
+
+            for &y in &ys {
+                for &x in &xs {
+                    let (ahead_x, ahead_y) = (x + dx, y + dy);
+                    let stop = if !jumps.contains((ahead_x, ahead_y)) {
+                        None
+                    } else if matches!(grid[ahead_y as usize][ahead_x as usize], '#' | 'O') {
+                        Some((x, y))
+                    } else {
+                        jumps.stops[jumps.index((ahead_x, ahead_y))][direction]
+                    };
+
+                    let index = jumps.index((x, y));
+                    jumps.stops[index][direction] = stop;
+                }
+            }
+        }
+
+        jumps
+    }
+
+    fn contains(&self, (x, y): Position) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    fn index(&self, (x, y): Position) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    /// Where the guard stops going from `position` in `direction`, with an extra `obstacle`.
+    fn stop(&self, (x, y): Position, direction: usize, (ox, oy): Position) -> Option<Position> {
+        let (dx, dy) = DIRECTIONS[direction];
+        let stop = self.stops[self.index((x, y))][direction];
+
+        let in_line = if dx != 0 { oy == y } else { ox == x };
+        let distance = (ox - x) * dx + (oy - y) * dy;
+        let blocks = in_line && distance >= 1 && stop.is_none_or(|(sx, sy)| distance <= (sx - x) * dx + (sy - y) * dy);
+
+        if blocks {
+            Some((ox - dx, oy - dy))
+        } else {
+            stop
+        }
+    }
+
+    /// The cells the guard at `position` facing `direction` turns at around a loop once `obstacle`
+    /// is added to the map, in order, or `None` if it walks off the map.
+    pub fn find_loop(&self, mut position: Position, mut direction: usize, obstacle: Position, context: &Context) -> Option<Vec<Position>> {
+        let mut turns = Vec::new();
+        let mut seen = HashMap::new();
+
+        while let Some(stop) = self.stop(position, direction, obstacle) {
+            context.step();
+
+            if let Some(&start) = seen.get(&(stop, direction)) {
+                return Some(turns.split_off(start));
+            }
+            seen.insert((stop, direction), turns.len());
+            turns.push(stop);
+
+            position = stop;
+            direction = (direction + 1) % 4;
+        }
+
+        None
+    }
+}
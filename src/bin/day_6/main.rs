@@ -1,5 +1,23 @@
+use advent_of_code_2024::validate;
+use advent_of_code_2024::{Context, Day};
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::HashSet;
+use jumps::{Jumps, DIRECTIONS};
+
+mod jumps;
+
+/// A cell of the map as `(x, y)`, or the step to the next cell in a direction.
+type Position = (isize, isize);
+
+/// Where a guard is and the way it faces.
+type Heading = (Position, Position);
+
+/// A cell of the guard's path, with where the guard is and the index of the way it faces in
+/// [`DIRECTIONS`] when about to step into it.
+type Arrival = (Position, Position, usize);
+
+/// The guards on the map and the way each of them faces.
+const GUARDS: [(char, Position); 4] = [('^', (0, -1)), ('>', (1, 0)), ('v', (0, 1)), ('<', (-1, 0))];
 
 enum GuardState {
     Inside,
@@ -8,32 +26,38 @@ enum GuardState {
 }
 
 struct Guard {
-    position: (isize, isize),
-    direction: (isize, isize),
-    distinct_positions: HashMap<(isize, isize), (isize, isize)>, // position -> direction
+    position: Position,
+    direction: Position,
+    distinct_positions: HashSet<Position>,
+    /// Every (position, direction) the guard has been in: being in one again means a loop.
+    visited: HashSet<Heading>,
     state: GuardState,
 }
 
 impl Guard {
-    fn new(x: isize, y: isize) -> Self {
+    fn new(position: Position, direction: Position) -> Self {
         Self {
-            position: (x, y),
-            direction: (0, -1),
-            distinct_positions: HashMap::new(),
+            position,
+            direction,
+            distinct_positions: HashSet::new(),
+            visited: HashSet::new(),
             state: GuardState::Inside,
         }
     }
 
     fn step(&mut self, grid: &Vec<Vec<char>>) {
-        if let Some(&direction) = self.distinct_positions.get(&self.position) {
-            if direction == self.direction {
-                self.state = GuardState::Stuck;
-                return;
-            }
+        if self.visited.contains(&(self.position, self.direction)) {
+            self.state = GuardState::Stuck;
+            return;
         }
 
-        self.distinct_positions.entry(self.position)
-            .or_insert(self.direction);
+        self.advance(grid, |_| false);
+    }
+
+    /// Moves or turns without looking for a loop, turning in front of `blocked` cells too.
+    fn advance(&mut self, grid: &Vec<Vec<char>>, blocked: impl Fn(Position) -> bool) {
+        self.visited.insert((self.position, self.direction));
+        self.distinct_positions.insert(self.position);
 
         let next_pos = (
             self.position.0 + self.direction.0,
@@ -41,19 +65,22 @@ impl Guard {
         );
 
         match at(grid, next_pos.0, next_pos.1) {
-            Some('#') | Some('O') => {
-                self.direction = match self.direction {
-                    (0, -1) => (1, 0),
-                    (1, 0) => (0, 1),
-                    (0, 1) => (-1, 0),
-                    (-1, 0) => (0, -1),
-                    _ => unreachable!(),
-                };
-            }
+            Some('#') | Some('O') => self.turn(),
+            Some(_) if blocked(next_pos) => self.turn(),
             None => self.state = GuardState::Escaped,
             _ => self.position = next_pos,
         };
     }
+
+    fn turn(&mut self) {
+        self.direction = match self.direction {
+            (0, -1) => (1, 0),
+            (1, 0) => (0, 1),
+            (0, 1) => (-1, 0),
+            (-1, 0) => (0, -1),
+            _ => unreachable!(),
+        };
+    }
 }
 
 fn at(grid: &Vec<Vec<char>>, x: isize, y: isize) -> Option<char> {
@@ -64,49 +91,319 @@ fn at(grid: &Vec<Vec<char>>, x: isize, y: isize) -> Option<char> {
         row.get(x as usize).copied())
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+struct Lab {
+    grid: Vec<Vec<char>>,
+    /// The position and direction of each guard, in reading order.
+    guards: Vec<Heading>,
+}
+
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::grid(input);
+    if !input.chars().any(|c| GUARDS.iter().any(|&(guard, _)| guard == c)) {
+        problems.push("there is no guard, expected one of ^, >, v and <".to_string());
+    }
+    problems
+}
 
+fn parse(input: &str) -> Lab {
     let grid = input
         .lines()
         .map(|line| line.chars()
             .collect::<Vec<_>>())
         .collect::<Vec<_>>();
 
-    let starting_pos = (|| {
-        for (y, row) in grid.iter().enumerate() {
-            for (x, &cell) in row.iter().enumerate() {
-                if cell == '^' {
-                    return (x as isize, y as isize);
-                }
+    let guards = grid.iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter()
+            .enumerate()
+            .filter_map(move |(x, cell)| GUARDS.iter()
+                .find(|(guard, _)| guard == cell)
+                .map(|&(_, direction)| ((x as isize, y as isize), direction))))
+        .collect::<Vec<_>>();
+
+    Lab { grid, guards }
+}
+
+fn patrol(grid: &Vec<Vec<char>>, (position, direction): Heading, context: &Context) -> Guard {
+    let mut guard = Guard::new(position, direction);
+    while let GuardState::Inside = guard.state {
+        context.step();
+        guard.step(grid);
+    }
+    guard
+}
+
+fn inside(guard: &Guard) -> bool {
+    matches!(guard.state, GuardState::Inside)
+}
+
+/// Every guard patrolling at once, each taking a step in turn in reading order.
+///
+/// With `blocking`, a guard turns in front of another one as in front of an obstacle. Their paths
+/// then depend on each other, so they are only stuck once all of them are back where they were at
+/// the same time.
+fn patrol_together(lab: &Lab, context: &Context) -> Vec<Guard> {
+    let blocking = context.param::<bool>("blocking");
+    let mut guards = lab.guards.iter()
+        .map(|&(position, direction)| Guard::new(position, direction))
+        .collect::<Vec<_>>();
+    let mut seen = HashSet::new();
+
+    while guards.iter().any(inside) {
+        context.step();
+
+        if blocking {
+            let together = guards.iter()
+                .map(|guard| (guard.position, guard.direction, inside(guard)))
+                .collect::<Vec<_>>();
+            if !seen.insert(together) {
+                guards.iter_mut()
+                    .filter(|guard| inside(guard))
+                    .for_each(|guard| guard.state = GuardState::Stuck);
+                break;
             }
         }
-        unreachable!();
-    })();
 
-    let mut guard = Guard::new(starting_pos.0, starting_pos.1);
+        for i in 0..guards.len() {
+            if !inside(&guards[i]) {
+                continue;
+            }
+
+            if blocking {
+                let others = guards.iter()
+                    .enumerate()
+                    .filter(|&(j, guard)| j != i && inside(guard))
+                    .map(|(_, guard)| guard.position)
+                    .collect::<HashSet<_>>();
+                guards[i].advance(&lab.grid, |position| others.contains(&position));
+            } else {
+                guards[i].step(&lab.grid);
+            }
+        }
+    }
+
+    guards
+}
+
+fn distinct_positions(lab: &Lab, context: &Context) -> usize {
+    patrol_together(lab, context).iter()
+        .flat_map(|guard| guard.distinct_positions.iter())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Each cell of the first guard's path but the start, with where the guard is and which way it
+/// faces when about to step into it for the first time.
+fn first_arrivals(lab: &Lab, context: &Context) -> Vec<Arrival> {
+    let (starting_pos, direction) = lab.guards[0];
+    let mut guard = Guard::new(starting_pos, direction);
+    let mut seen = HashSet::from([starting_pos]);
+    let mut arrivals = Vec::new();
+
     while let GuardState::Inside = guard.state {
-        guard.step(&grid);
+        context.step();
+
+        let (position, direction) = (guard.position, guard.direction);
+        guard.step(&lab.grid);
+        if seen.insert(guard.position) {
+            let direction = DIRECTIONS.iter().position(|&d| d == direction).unwrap();
+            arrivals.push((guard.position, position, direction));
+        }
     }
 
-    println!("Distinct positions: {}", guard.distinct_positions.len());
+    arrivals
+}
+
+/// For the first guard alone. Its path is the same up to the obstacle, so each check starts right
+/// in front of it.
+fn obstacles(lab: &Lab, context: &Context) -> usize {
+    let jumps = Jumps::new(&lab.grid);
+
+    first_arrivals(lab, context).par_iter()
+        .filter(|&&(obstacle, position, direction)| jumps.find_loop(position, direction, obstacle, context).is_some())
+        .count()
+}
+
+/// Every obstacle that traps the guard in reading order, with the cells it turns at around the loop.
+fn trapping_obstacles(lab: &Lab, context: &Context) -> Vec<(Position, Vec<Position>)> {
+    let jumps = Jumps::new(&lab.grid);
+
+    let mut trapping = first_arrivals(lab, context).par_iter()
+        .filter_map(|&(obstacle, position, direction)| jumps.find_loop(position, direction, obstacle, context)
+            .map(|corners| (obstacle, corners)))
+        .collect::<Vec<_>>();
+    trapping.sort_by_key(|&((x, y), _)| (y, x));
+    trapping
+}
+
+/// The steps to go once around the loop through these corners.
+fn loop_length(corners: &[Position]) -> usize {
+    corners.iter()
+        .zip(corners.iter().cycle().skip(1))
+        .map(|(a, b)| a.0.abs_diff(b.0) + a.1.abs_diff(b.1))
+        .sum()
+}
+
+/// The map with the loop drawn the way the puzzle does, `|` and `-` along it and `+` where it
+/// turns or crosses itself, and the added obstacle as `O`.
+fn render(grid: &[Vec<char>], obstacle: Position, corners: &[Position]) -> String {
+    let mut grid = grid.to_vec();
+
+    for (&from, &to) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+        let step = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+        let line = if step.0 != 0 { '-' } else { '|' };
+
+        let mut position = from;
+        loop {
+            let cell = &mut grid[position.1 as usize][position.0 as usize];
+            *cell = match *cell {
+                '.' if position != from && position != to => line,
+                '.' | '|' | '-' | '+' => '+',
+                cell => cell,
+            };
+
+            if position == to {
+                break;
+            }
+            position = (position.0 + step.0, position.1 + step.1);
+        }
+    }
+
+    grid[obstacle.1 as usize][obstacle.0 as usize] = 'O';
+    grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+}
+
+fn obstacles_step_by_step(lab: &Lab, context: &Context) -> usize {
+    let Lab { grid, guards } = lab;
+    let (starting_pos, _) = &guards[0];
 
-    let distinct_positions = guard.distinct_positions.keys()
-        .filter(|&&pos| pos != starting_pos)
+    let distinct_positions = patrol(grid, guards[0], context).distinct_positions.iter()
+        .filter(|&pos| pos != starting_pos)
         .copied()
         .collect::<Vec<_>>();
 
-    let obstacles: usize = distinct_positions.par_iter().map(|&pos| {
+    distinct_positions.par_iter().map(|&pos| {
         let mut grid = grid.clone();
         grid[pos.1 as usize][pos.0 as usize] = 'O';
 
-        let mut guard = Guard::new(starting_pos.0, starting_pos.1);
-        while let GuardState::Inside = guard.state {
-            guard.step(&grid);
-        }
+        let guard = patrol(&grid, guards[0], context);
 
         if let GuardState::Stuck = guard.state { 1 } else { 0 }
-    }).sum();
+    }).sum()
+}
+
+fn main() {
+    Day::new("day_6", include_str!("input.txt"), validate, parse)
+        .param("blocking", false)
+        .part("Distinct positions", distinct_positions)
+        .part("Obstacles", obstacles)
+        .alternative("step_by_step", obstacles_step_by_step)
+        .show_grid("the lab", |lab| &lab.grid)
+        .command("loops", "loops [render]: every obstacle that traps the guard with the length of its loop, or also the loop on the map", |lab, context, args| {
+            let render_loops = match args {
+                [] => false,
+                ["render"] => true,
+                _ => return Err("usage: loops [render]".to_string()),
+            };
+
+            Ok(trapping_obstacles(lab, context).iter()
+                .map(|(obstacle, corners)| {
+                    let line = format!("{},{}: loop of {} steps", obstacle.0, obstacle.1, loop_length(corners));
+                    if render_loops {
+                        format!("{}\n{}", line, render(&lab.grid, *obstacle, corners))
+                    } else {
+                        line
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(if render_loops { "\n\n" } else { "\n" }))
+        })
+        .command("guards", "guards: where each guard starts and how its patrol ends, with the others as obstacles if blocking", |lab, context, args| {
+            if !args.is_empty() {
+                return Err("usage: guards".to_string());
+            }
 
-    println!("Obstacles: {}", obstacles);
+            Ok(lab.guards.iter()
+                .zip(patrol_together(lab, context))
+                .map(|(&((x, y), direction), guard)| {
+                    let (facing, _) = GUARDS.iter().find(|&&(_, d)| d == direction).unwrap();
+                    let end = match guard.state {
+                        GuardState::Escaped => "escapes",
+                        _ => "loops",
+                    };
+                    format!("{} at {},{}: {} after {} distinct positions", facing, x, y, end, guard.distinct_positions.len())
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        })
+        .run();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walks the guard until it leaves the map or loops.
+    fn walk(map: &str) -> Guard {
+        let lab = parse(map);
+        let mut guard = Guard::new(lab.guards[0].0, lab.guards[0].1);
+        for _ in 0..10_000 {
+            if !matches!(guard.state, GuardState::Inside) {
+                return guard;
+            }
+            guard.step(&lab.grid);
+        }
+        panic!("the guard neither escaped nor was found stuck");
+    }
+
+    #[test]
+    fn counts_distinct_positions_of_an_escape() {
+        let guard = walk("\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...");
+
+        assert!(matches!(guard.state, GuardState::Escaped));
+        assert_eq!(guard.distinct_positions.len(), 41);
+    }
+
+    #[test]
+    fn stops_when_turning_back_into_a_loop() {
+        // The guard turns in place facing north, east, south then west, steps west and turns
+        // twice back east. Facing east at the start again closes the loop, where keeping only the
+        // first direction at each cell went on until facing west one cell to the west.
+        let guard = walk("\
+....#
+.##..
+#.^#.
+.##..");
+
+        assert!(matches!(guard.state, GuardState::Stuck));
+        assert_eq!((guard.position, guard.direction), ((2, 2), (1, 0)));
+        assert_eq!(guard.visited.len(), 7);
+        assert_eq!(guard.distinct_positions.len(), 2);
+    }
+
+    #[test]
+    fn stops_when_crossing_back_into_a_loop() {
+        // The loop goes east through the cell north of the start, first crossed northward, which
+        // keeping only the first direction at each cell took four more steps to notice.
+        let guard = walk("\
+.##...
+...#.#
+#.^#.#
+..#..#");
+
+        assert!(matches!(guard.state, GuardState::Stuck));
+        assert_eq!((guard.position, guard.direction), ((2, 1), (1, 0)));
+        assert_eq!(guard.visited.len(), 10);
+        assert_eq!(guard.distinct_positions.len(), 4);
+    }
 }
@@ -1,16 +1,23 @@
-use std::ops::{Add, Mul};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use advent_of_code_2024::checked::{self, Wide};
+use advent_of_code_2024::validate;
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
 use rayon::prelude::*;
 
 fn total_calibration_result(
-    equations: &[(usize, Vec<usize>)],
-    operations: &[fn(x: usize, y: usize) -> usize],
-) -> usize {
+    equations: &[(Wide, Vec<Wide>)],
+    operations: &[fn(x: Wide, y: Wide) -> Wide],
+    context: &Context,
+) -> Wide {
     equations.par_iter().filter_map(|(test_value, terms)| {
         (0..terms.len())
             .map(|_| operations)
             .multi_cartesian_product()
             .find_map(|ops| {
+                context.step();
+
                 let mut i = 0;
                 let result = terms.iter().copied().reduce(|result, term| {
                     let result = ops[i](result, term);
@@ -19,24 +26,87 @@ fn total_calibration_result(
                 }).unwrap();
                 if result == *test_value { Some(*test_value) } else { None }
             })
-    }).sum()
+    }).reduce(|| 0, |sum, test_value| checked::add(sum, test_value))
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn concat(a: Wide, b: Wide) -> Wide { checked::add(checked::mul(a, checked::pow(10, b.ilog10() + 1)), b) }
+
+/// The operators to try between terms, by their symbols like `+*|`.
+struct Operators(String);
+
+impl FromStr for Operators {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(symbol) = s.chars().find(|symbol| !"+*|".contains(*symbol)) {
+            return Err(format!("unknown operator `{}`, expected one of `+`, `*` or `|`", symbol));
+        }
+        if s.is_empty() {
+            return Err("expected at least one of `+`, `*` or `|`".to_string());
+        }
+
+        Ok(Operators(s.to_string()))
+    }
+}
+
+impl Display for Operators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
 
-    let equations = input
+fn operations(Operators(symbols): &Operators) -> Vec<fn(x: Wide, y: Wide) -> Wide> {
+    symbols.chars().map(|symbol| match symbol {
+        '+' => |x, y| checked::add(x, y),
+        '*' => |x, y| checked::mul(x, y),
+        '|' => concat,
+        _ => unreachable!(),
+    }).collect_vec()
+}
+
+fn validate(input: &str) -> Vec<String> {
+    if input.trim().is_empty() {
+        return vec!["the input is empty, expected equations".to_string()];
+    }
+
+    validate::lines(input, 1, |equation| {
+        let (test_value, terms) = equation.split_once(": ")
+            .ok_or_else(|| format!("`{}` is not <test value>: <numbers>", equation))?;
+
+        if test_value.parse::<Wide>().is_err() {
+            return Err(format!("`{}` is not a test value", test_value));
+        }
+        // Concatenation counts the digits of the terms, which zero has none of.
+        match terms.split(' ').find(|term| term.parse::<Wide>().map_or(true, |term| term == 0)) {
+            Some(term) => Err(format!("`{}` is not a positive number", term)),
+            None => Ok(()),
+        }
+    })
+}
+
+fn parse(input: &str) -> Vec<(Wide, Vec<Wide>)> {
+    input
         .lines()
         .map(|line| {
             let (test_value, terms) = line.split_once(": ").unwrap();
             (
-                test_value.parse::<usize>().unwrap(),
-                terms.split(" ").map(|term| term.parse::<usize>().unwrap()).collect_vec(),
+                test_value.parse::<Wide>().unwrap(),
+                terms.split(" ").map(|term| term.parse::<Wide>().unwrap()).collect_vec(),
             )
-        }).collect_vec();
-
-    println!("Part 1: {}", total_calibration_result(&equations, &[Add::add, Mul::mul]));
+        }).collect_vec()
+}
 
-    fn concat(a: usize, b: usize) -> usize { a * 10_usize.pow(b.ilog10() + 1) + b }
-    println!("Part 2: {}", total_calibration_result(&equations, &[Add::add, Mul::mul, concat]));
+fn main() {
+    Day::new("day_7", include_str!("input.txt"), validate, parse)
+        .param("part_1_operators", Operators("+*".to_string()))
+        .param("part_2_operators", Operators("+*|".to_string()))
+        .part("Part 1", |equations, context| {
+            let operations = operations(&context.param("part_1_operators"));
+            total_calibration_result(equations, &operations, context)
+        })
+        .part("Part 2", |equations, context| {
+            let operations = operations(&context.param("part_2_operators"));
+            total_calibration_result(equations, &operations, context)
+        })
+        .run();
 }
@@ -1,3 +1,5 @@
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 use itertools::Itertools;
 use std::collections::HashSet;
 
@@ -56,62 +58,82 @@ fn antinodes_in_direction<'a>(
     direction: (isize, isize),
 ) -> impl Iterator<Item=(isize, isize)> + 'a {
     (0..).scan(start, move |pos, _| {
-        pos.0 += direction.0;
-        pos.1 += direction.1;
+        pos.0 = checked::add(pos.0, direction.0);
+        pos.1 = checked::add(pos.1, direction.1);
         grid.at(*pos).map(|_| *pos)
     })
 }
 
-fn main() {
-    let input = include_str!("input.txt");
+fn validate(input: &str) -> Vec<String> {
+    let mut problems = validate::grid(input);
+    problems.extend(validate::cells(input, "`.` or an antenna", |c| c == '.' || c.is_ascii_alphanumeric()));
+    problems
+}
 
-    let grid = input.lines().map(|line| line.chars().collect_vec()).collect_vec();
+fn parse(input: &str) -> Vec<Vec<char>> {
+    input.lines().map(|line| line.chars().collect_vec()).collect_vec()
+}
 
+fn antenna_combinations(grid: &Vec<Vec<char>>) -> Vec<((isize, isize), (isize, isize))> {
     let antennas = grid.grid_iter()
         .filter(|&(cell, _)| cell != '.')
         .into_grouping_map()
         .collect::<Vec<_>>();
 
-    let antenna_combinations = antennas.iter()
+    antennas.iter()
         .map(|(_, positions)| positions.iter().copied()
             .combinations(2)
             .map(|pair| (pair[0], pair[1])))
         .flatten()
-        .collect_vec();
+        .collect_vec()
+}
 
+fn part_1(grid: &Vec<Vec<char>>, _: &Context) -> usize {
     let mut antinodes = HashSet::new();
 
-    for pair in &antenna_combinations {
+    for pair in &antenna_combinations(grid) {
         let first = pair.0;
         let second = pair.1;
 
-        let direction = (second.0 - first.0, second.1 - first.1);
+        let direction = (checked::sub(second.0, first.0), checked::sub(second.1, first.1));
         let opposite_direction = (-direction.0, -direction.1);
 
-        if let Some(antinode) = antinodes_in_direction(&grid, second, direction).next() {
+        if let Some(antinode) = antinodes_in_direction(grid, second, direction).next() {
             antinodes.insert(antinode);
         }
 
-        if let Some(antinode) = antinodes_in_direction(&grid, first, opposite_direction).next() {
+        if let Some(antinode) = antinodes_in_direction(grid, first, opposite_direction).next() {
             antinodes.insert(antinode);
         }
     }
 
-    println!("Part 1: {}", antinodes.len());
+    antinodes.len()
+}
+
+fn part_2(grid: &Vec<Vec<char>>, _: &Context) -> usize {
+    let mut antinodes = HashSet::new();
 
-    for pair in &antenna_combinations {
+    for pair in &antenna_combinations(grid) {
         let first = pair.0;
         let second = pair.1;
 
         antinodes.insert(first);
         antinodes.insert(second);
 
-        let direction = (second.0 - first.0, second.1 - first.1);
+        let direction = (checked::sub(second.0, first.0), checked::sub(second.1, first.1));
         let opposite_direction = (-direction.0, -direction.1);
 
-        antinodes.extend(antinodes_in_direction(&grid, second, direction));
-        antinodes.extend(antinodes_in_direction(&grid, first, opposite_direction));
+        antinodes.extend(antinodes_in_direction(grid, second, direction));
+        antinodes.extend(antinodes_in_direction(grid, first, opposite_direction));
     }
 
-    println!("Part 2: {}", antinodes.len());
+    antinodes.len()
+}
+
+fn main() {
+    Day::new("day_8", include_str!("input.txt"), validate, parse)
+        .part("Part 1", part_1)
+        .part("Part 2", part_2)
+        .show_grid("the antenna map", |grid| grid)
+        .run();
 }
@@ -1,4 +1,6 @@
 use std::iter::repeat;
+use advent_of_code_2024::{checked, validate};
+use advent_of_code_2024::{Context, Day};
 
 #[derive(Debug)]
 struct Block {
@@ -6,18 +8,18 @@ struct Block {
     id: Option<i64>,
 }
 
-fn main() {
-    let input = include_str!("input.txt");
-
-    let disk_map = input.chars().map(|x| x.to_digit(10).unwrap() as usize).collect::<Vec<_>>();
+fn parse(input: &str) -> Vec<usize> {
+    input.chars().map(|x| x.to_digit(10).unwrap() as usize).collect::<Vec<_>>()
+}
 
+fn part_1(disk_map: &Vec<usize>, _: &Context) -> i64 {
     let filesystem_size = disk_map.iter().sum();
     let mut filesystem = Vec::<i64>::with_capacity(filesystem_size);
 
     let mut is_file = true;
     let mut next_id = 0;
 
-    for &n in &disk_map {
+    for &n in disk_map {
         filesystem.extend(repeat(if is_file {
             let id = next_id;
             next_id += 1;
@@ -49,10 +51,14 @@ fn main() {
         if id == -1 {
             break;
         }
-        checksum += i as i64 * id;
+        checksum = checked::add(checksum, checked::mul(i as i64, id));
     }
 
-    println!("Part 1: {}", checksum);
+    checksum
+}
+
+fn part_2(disk_map: &Vec<usize>, _: &Context) -> i64 {
+    let next_id = disk_map.len().div_ceil(2) as i64;
 
     let mut disk_map = disk_map.iter().scan((0, true), |(id, is_file), &n| {
         let block = Block {
@@ -95,11 +101,18 @@ fn main() {
     for block in disk_map.iter() {
         if let Some(id) = block.id {
             for i in pos..pos + block.size {
-                checksum += i as i64 * id;
+                checksum = checked::add(checksum, checked::mul(i as i64, id));
             }
         }
         pos += block.size;
     }
 
-    println!("Part 2: {}", checksum);
+    checksum
+}
+
+fn main() {
+    Day::new("day_9", include_str!("input.txt"), validate::digits, parse)
+        .part("Part 1", part_1)
+        .part("Part 2", part_2)
+        .run();
 }
@@ -0,0 +1,77 @@
+use std::fmt::{self, Display};
+use std::panic;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long a part may run, from `--max-steps` and `--timeout`.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct Limits {
+    pub max_steps: Option<u64>,
+    pub timeout: Option<Duration>,
+}
+
+/// Panic payload of a solver that ran out of budget or was cancelled.
+#[derive(Debug)]
+pub struct Timeout {
+    pub steps: u64,
+}
+
+impl Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out after {} steps", self.steps)
+    }
+}
+
+/// Steps taken by the running solver, and the token that cancels it.
+pub(crate) struct Budget {
+    limits: Limits,
+    steps: AtomicU64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Budget {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            steps: AtomicU64::new(0),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Runs a solver with a fresh budget, cancelling it from a watchdog thread once its time is
+    /// up. The solver must not unwind, wrap it in [`guarded`](crate::day::guarded) first.
+    pub fn run<R>(&self, solve: impl FnOnce() -> R) -> R {
+        self.steps.store(0, Ordering::Relaxed);
+        self.cancelled.store(false, Ordering::Relaxed);
+
+        let Some(timeout) = self.limits.timeout else {
+            return solve();
+        };
+
+        let (done, finished) = mpsc::channel::<()>();
+        let cancelled = self.cancelled.clone();
+        let watchdog = thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(timeout) {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+        });
+
+        let result = solve();
+
+        drop(done);
+        watchdog.join().unwrap();
+        result
+    }
+
+    pub fn step(&self) {
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed);
+
+        let exhausted = self.limits.max_steps.is_some_and(|max_steps| steps >= max_steps);
+        if exhausted || self.cancelled.load(Ordering::Relaxed) {
+            panic::panic_any(Timeout { steps });
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Arithmetic for values that can outgrow their type.
+//!
+//! Without the `checked` feature these are the plain operators: they panic in debug builds and
+//! wrap in release builds. With it, every operation is checked and an overflow unwinds with an
+//! [`Overflow`] that the runner reports together with the day and part that caused it.
+
+use std::fmt::{self, Display};
+use std::ops::{Add, Mul, Shl, Shr, Sub};
+use std::panic::{self, Location};
+
+/// Integer type for the values known to grow, widened to `u128` by the `u128` feature.
+#[cfg(not(feature = "u128"))]
+pub type Wide = usize;
+
+/// Integer type for the values known to grow, widened to `u128` by the `u128` feature.
+#[cfg(feature = "u128")]
+pub type Wide = u128;
+
+/// Panic payload of an overflowing checked operation.
+#[derive(Debug)]
+pub struct Overflow {
+    pub operation: String,
+    pub location: &'static Location<'static>,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.operation, self.location)
+    }
+}
+
+pub trait Int:
+    Copy + Display
+    + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self>
+    + Shl<u32, Output=Self> + Shr<u32, Output=Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const BITS: u32;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    fn pow(self, exp: u32) -> Self;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const BITS: u32 = <$t>::BITS;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> { <$t>::checked_add(self, rhs) }
+                fn checked_sub(self, rhs: Self) -> Option<Self> { <$t>::checked_sub(self, rhs) }
+                fn checked_mul(self, rhs: Self) -> Option<Self> { <$t>::checked_mul(self, rhs) }
+                fn checked_pow(self, exp: u32) -> Option<Self> { <$t>::checked_pow(self, exp) }
+                fn pow(self, exp: u32) -> Self { <$t>::pow(self, exp) }
+            }
+        )*
+    };
+}
+
+impl_int!(i32, i64, isize, u32, u64, usize, u128);
+
+fn check<T>(result: Option<T>, operation: impl FnOnce() -> String, location: &'static Location<'static>) -> T {
+    result.unwrap_or_else(|| panic::panic_any(Overflow {
+        operation: operation(),
+        location,
+    }))
+}
+
+#[track_caller]
+pub fn add<T: Int>(lhs: T, rhs: T) -> T {
+    if cfg!(feature = "checked") {
+        check(lhs.checked_add(rhs), || format!("{} + {}", lhs, rhs), Location::caller())
+    } else {
+        lhs + rhs
+    }
+}
+
+#[track_caller]
+pub fn sub<T: Int>(lhs: T, rhs: T) -> T {
+    if cfg!(feature = "checked") {
+        check(lhs.checked_sub(rhs), || format!("{} - {}", lhs, rhs), Location::caller())
+    } else {
+        lhs - rhs
+    }
+}
+
+#[track_caller]
+pub fn mul<T: Int>(lhs: T, rhs: T) -> T {
+    if cfg!(feature = "checked") {
+        check(lhs.checked_mul(rhs), || format!("{} * {}", lhs, rhs), Location::caller())
+    } else {
+        lhs * rhs
+    }
+}
+
+#[track_caller]
+pub fn pow<T: Int>(base: T, exp: u32) -> T {
+    if cfg!(feature = "checked") {
+        check(base.checked_pow(exp), || format!("{} ^ {}", base, exp), Location::caller())
+    } else {
+        base.pow(exp)
+    }
+}
+
+/// `value << shift`, which also overflows when bits are shifted out.
+#[track_caller]
+pub fn shl<T: Int + PartialEq>(value: T, shift: usize) -> T {
+    if cfg!(feature = "checked") {
+        let shifted = u32::try_from(shift).ok()
+            .filter(|&shift| shift < T::BITS)
+            .map(|shift| (value << shift, shift))
+            .filter(|&(shifted, shift)| shifted >> shift == value)
+            .map(|(shifted, _)| shifted);
+        check(shifted, || format!("{} << {}", value, shift), Location::caller())
+    } else {
+        value << shift as u32
+    }
+}
+
+#[track_caller]
+pub fn shr<T: Int>(value: T, shift: usize) -> T {
+    if cfg!(feature = "checked") {
+        let shifted = u32::try_from(shift).ok()
+            .filter(|&shift| shift < T::BITS)
+            .map(|shift| value >> shift);
+        check(shifted, || format!("{} >> {}", value, shift), Location::caller())
+    } else {
+        value >> shift as u32
+    }
+}
+
+#[track_caller]
+pub fn sum<T: Int>(values: impl IntoIterator<Item=T>) -> T {
+    let location = Location::caller();
+    values.into_iter().fold(T::ZERO, |sum, value| {
+        if cfg!(feature = "checked") {
+            check(sum.checked_add(value), || format!("{} + {}", sum, value), location)
+        } else {
+            sum + value
+        }
+    })
+}
+
+#[track_caller]
+pub fn product<T: Int>(values: impl IntoIterator<Item=T>) -> T {
+    let location = Location::caller();
+    values.into_iter().fold(T::ONE, |product, value| {
+        if cfg!(feature = "checked") {
+            check(product.checked_mul(value), || format!("{} * {}", product, value), location)
+        } else {
+            product * value
+        }
+    })
+}
@@ -0,0 +1,390 @@
+use std::env;
+use std::fmt::{self, Display};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use crate::args::{Args, Command};
+use crate::budget::{Budget, Timeout};
+use crate::checked::Overflow;
+use crate::params::{Param, Params};
+use crate::repl;
+
+/// What a part gets to see besides the parsed puzzle.
+pub struct Context {
+    pub(crate) params: Params,
+    pub(crate) budget: Budget,
+}
+
+impl Context {
+    /// Counts one step of the solver. Once the step or time budget is exhausted, the solver
+    /// unwinds and the runner reports it as timed out.
+    ///
+    /// Loops that are not obviously bounded should take a step per iteration.
+    pub fn step(&self) {
+        self.budget.step();
+    }
+
+    /// Value of a parameter declared with [`Day::param`], after command line overrides.
+    pub fn param<T>(&self, key: &str) -> T
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.params.get(key)
+    }
+
+    /// Gives up on the puzzle when it has no answer, for instance because of its parameters. The
+    /// runner reports `reason` instead of the answer, like an overflow or an exhausted budget.
+    pub fn fail(&self, reason: impl Display) -> ! {
+        panic::panic_any(Unsolvable(reason.to_string()))
+    }
+}
+
+/// Panic payload of [`Context::fail`].
+#[derive(Debug)]
+pub(crate) struct Unsolvable(String);
+
+type Solve<P> = Box<dyn Fn(&P, &Context) -> String>;
+
+type Execute<P> = Box<dyn Fn(&P, &Context, &[&str]) -> Result<String, String>>;
+
+/// One way of solving a part. The first one registered is `default`, the one used to solve.
+pub(crate) struct Implementation<P> {
+    pub name: &'static str,
+    pub solve: Solve<P>,
+}
+
+pub(crate) struct Part<P> {
+    pub label: &'static str,
+    pub implementations: Vec<Implementation<P>>,
+}
+
+/// A REPL command for inspecting a parsed puzzle.
+pub(crate) struct ReplCommand<P> {
+    pub name: &'static str,
+    pub usage: String,
+    pub execute: Execute<P>,
+}
+
+/// A puzzle day: how to parse its input, its parameters and its parts.
+///
+/// ```text
+/// day_14 [repl | compare | check [<file>] | <command>...] [--input <file>] [--param <key>=<value>]... [--max-steps <n>] [--timeout <duration>]
+/// ```
+pub struct Day<P> {
+    pub(crate) name: &'static str,
+    input: &'static str,
+    parse: fn(&'static str) -> P,
+    validate: fn(&str) -> Vec<String>,
+    pub(crate) params: Vec<Param>,
+    pub(crate) parts: Vec<Part<P>>,
+    pub(crate) commands: Vec<ReplCommand<P>>,
+}
+
+impl<P> Day<P> {
+    /// `validate` checks the raw input against the structure the day expects, with the checks of
+    /// [`validate`](crate::validate). It runs before `parse` and every problem it returns is
+    /// reported.
+    pub fn new(
+        name: &'static str,
+        input: &'static str,
+        validate: fn(&str) -> Vec<String>,
+        parse: fn(&'static str) -> P,
+    ) -> Self {
+        Self {
+            name,
+            input,
+            parse,
+            validate,
+            params: Vec::new(),
+            parts: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Declares a parameter that can be overridden with `--param <key>=<value>`.
+    pub fn param<T>(mut self, key: &'static str, default: T) -> Self
+    where
+        T: FromStr + Display,
+        T::Err: Display,
+    {
+        self.params.push(Param::new(key, default));
+        self
+    }
+
+    pub fn part<R: Display>(mut self, label: &'static str, solve: impl Fn(&P, &Context) -> R + 'static) -> Self {
+        self.parts.push(Part {
+            label,
+            implementations: vec![Implementation {
+                name: "default",
+                solve: Box::new(move |puzzle, context| solve(puzzle, context).to_string()),
+            }],
+        });
+        self
+    }
+
+    /// Registers another implementation of the last declared part, run by `compare` against the
+    /// default one.
+    pub fn alternative<R: Display>(mut self, name: &'static str, solve: impl Fn(&P, &Context) -> R + 'static) -> Self {
+        let part = self.parts.last_mut()
+            .unwrap_or_else(|| panic!("alternative `{}` declared before any part", name));
+
+        part.implementations.push(Implementation {
+            name,
+            solve: Box::new(move |puzzle, context| solve(puzzle, context).to_string()),
+        });
+        self
+    }
+
+    /// Registers a REPL command, called with the words following its name.
+    ///
+    /// `usage` is shown by `help`, and returned errors are printed as they are.
+    pub fn command<R: Display>(
+        mut self,
+        name: &'static str,
+        usage: &'static str,
+        execute: impl Fn(&P, &Context, &[&str]) -> Result<R, String> + 'static,
+    ) -> Self {
+        self.commands.push(ReplCommand {
+            name,
+            usage: usage.to_string(),
+            execute: Box::new(move |puzzle, context, args| {
+                execute(puzzle, context, args).map(|output| output.to_string())
+            }),
+        });
+        self
+    }
+
+    /// Registers `show grid`, printing the grid picked out of the puzzle. `what` names the grid
+    /// in the usage, like `the lab`.
+    pub fn show_grid<T: Display + 'static>(mut self, what: &str, grid: impl Fn(&P) -> &Vec<Vec<T>> + 'static) -> Self {
+        self.commands.push(ReplCommand {
+            name: "show",
+            usage: format!("show grid: print {}", what),
+            execute: Box::new(move |puzzle, _, args| match args {
+                ["grid"] => Ok(render_grid(grid(puzzle))),
+                _ => Err("usage: show grid".to_string()),
+            }),
+        });
+        self
+    }
+
+    pub fn run(self) {
+        let args = Args::parse(env::args().skip(1))
+            .unwrap_or_else(|e| self.exit_with_usage(&e));
+
+        if args.help {
+            println!("{}", self.usage());
+            return;
+        }
+
+        let params = Params::new(&self.params, &args.params)
+            .unwrap_or_else(|e| self.exit_with_usage(&e));
+
+        let input = match args.input {
+            Some(path) => fs::read_to_string(&path)
+                .unwrap_or_else(|e| self.exit_with_usage(&format!("cannot read `{}`: {}", path, e)))
+                .leak(),
+            None => self.input,
+        };
+
+        let problems = (self.validate)(input);
+        if let Command::Check = args.command {
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            if !problems.is_empty() {
+                process::exit(1);
+            }
+            println!("input is valid");
+            return;
+        }
+        if !problems.is_empty() {
+            for problem in &problems {
+                eprintln!("{}: {}", self.name, problem);
+            }
+            process::exit(1);
+        }
+
+        let mut context = Context {
+            params,
+            budget: Budget::new(args.limits),
+        };
+        let puzzle = (self.parse)(input);
+
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let payload = info.payload();
+            if !payload.is::<Overflow>() && !payload.is::<Timeout>() && !payload.is::<Unsolvable>() {
+                default_hook(info);
+            }
+        }));
+
+        match args.command {
+            Command::Solve => {
+                if !self.solve(&puzzle, &context) {
+                    process::exit(1);
+                }
+            }
+            Command::Compare => {
+                if !self.compare(&puzzle, &context) {
+                    process::exit(1);
+                }
+            }
+            Command::Repl => repl::repl(&self, &puzzle, &mut context),
+            Command::Execute(words) => {
+                let words = words.iter().map(String::as_str).collect::<Vec<_>>();
+                let Some(command) = self.commands.iter().find(|command| command.name == words[0]) else {
+                    self.exit_with_usage(&format!("unknown command `{}`", words[0]));
+                };
+
+                match context.budget.run(|| guarded(|| (command.execute)(&puzzle, &context, &words[1..]))) {
+                    Ok(Ok(output)) => println!("{}", output),
+                    Ok(Err(e)) => {
+                        eprintln!("{}: {}", self.name, e);
+                        process::exit(1);
+                    }
+                    Err(failure) => {
+                        eprintln!("{}: {} {}", self.name, command.name, failure);
+                        process::exit(1);
+                    }
+                }
+            }
+            Command::Check => unreachable!(),
+        }
+    }
+
+    /// Prints the answer of every part, returns whether all of them could be solved.
+    pub(crate) fn solve(&self, puzzle: &P, context: &Context) -> bool {
+        let mut solved = true;
+
+        for part in &self.parts {
+            let solve = &part.implementations[0].solve;
+            match context.budget.run(|| guarded(|| solve(puzzle, context))) {
+                Ok(answer) => println!("{}: {}", part.label, answer),
+                Err(failure) => {
+                    eprintln!("{}: {} {}", self.name, part.label, failure);
+                    solved = false;
+                }
+            }
+        }
+
+        solved
+    }
+
+    /// Runs every implementation of every part and prints their answers with their timings
+    /// relative to the default one, returns whether all of them agree.
+    pub(crate) fn compare(&self, puzzle: &P, context: &Context) -> bool {
+        let mut agreed = true;
+
+        for part in &self.parts {
+            let runs = part.implementations.iter()
+                .map(|implementation| {
+                    let start = Instant::now();
+                    let answer = context.budget.run(|| guarded(|| (implementation.solve)(puzzle, context)));
+                    (implementation.name, answer.map_err(|failure| failure.to_string()), start.elapsed())
+                })
+                .collect::<Vec<_>>();
+
+            let name_width = runs.iter().map(|(name, _, _)| name.len()).max().unwrap_or(0);
+            let answer_width = runs.iter()
+                .map(|(_, answer, _)| answer.as_ref().unwrap_or_else(|failure| failure).len())
+                .max()
+                .unwrap_or(0);
+            let reference = runs[0].2.max(Duration::from_nanos(1));
+
+            println!("{}:", part.label);
+            for (name, answer, elapsed) in &runs {
+                println!(
+                    "    {:name_width$}  {:>answer_width$}  {:>10.2?}  {:.2}x",
+                    name,
+                    answer.as_ref().unwrap_or_else(|failure| failure),
+                    elapsed,
+                    elapsed.as_secs_f64() / reference.as_secs_f64(),
+                );
+            }
+
+            let answers = runs.iter().map(|(_, answer, _)| answer).collect::<Vec<_>>();
+            if answers.iter().any(|answer| answer.is_err()) {
+                eprintln!("{}: {} could not be solved by every implementation", self.name, part.label);
+                agreed = false;
+            } else if answers.windows(2).any(|pair| pair[0] != pair[1]) {
+                eprintln!("{}: {} implementations disagree", self.name, part.label);
+                agreed = false;
+            }
+        }
+
+        agreed
+    }
+
+    fn usage(&self) -> String {
+        let mut usage = format!(
+            "usage: {} [repl | compare | check [<file>] | <command>...] [--input <file>] [--param <key>=<value>]... [--max-steps <n>] [--timeout <duration>]",
+            self.name,
+        );
+
+        if !self.params.is_empty() {
+            usage += "\n\nparameters, set with --<key> <value> or --param <key>=<value>:";
+            for param in &self.params {
+                usage += &format!("\n    {} (default: {})", param.key, param.default);
+            }
+        }
+
+        if !self.commands.is_empty() {
+            usage += "\n\ncommands, also available in the REPL:";
+            for command in &self.commands {
+                usage += &format!("\n    {}", command.usage);
+            }
+        }
+
+        usage
+    }
+
+    fn exit_with_usage(&self, message: &str) -> ! {
+        eprintln!("{}: {}\n\n{}", self.name, message, self.usage());
+        process::exit(2);
+    }
+}
+
+/// Why a solver did not produce an answer.
+pub(crate) enum Failure {
+    Overflow(Box<Overflow>),
+    Timeout(Box<Timeout>),
+    Unsolvable(Box<Unsolvable>),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Overflow(overflow) => write!(f, "overflowed: {}", overflow),
+            Failure::Timeout(timeout) => write!(f, "{}", timeout),
+            Failure::Unsolvable(unsolvable) => write!(f, "failed: {}", unsolvable.0),
+        }
+    }
+}
+
+/// Runs a solver, turning overflows of checked arithmetic and exhausted budgets into failures.
+pub(crate) fn guarded<R>(solve: impl FnOnce() -> R) -> Result<R, Failure> {
+    panic::catch_unwind(AssertUnwindSafe(solve)).map_err(|payload| {
+        match payload.downcast::<Overflow>() {
+            Ok(overflow) => Failure::Overflow(overflow),
+            Err(payload) => match payload.downcast::<Timeout>() {
+                Ok(timeout) => Failure::Timeout(timeout),
+                Err(payload) => match payload.downcast::<Unsolvable>() {
+                    Ok(unsolvable) => Failure::Unsolvable(unsolvable),
+                    Err(payload) => panic::resume_unwind(payload),
+                },
+            },
+        }
+    })
+}
+
+/// A grid as text, a row per line.
+pub fn render_grid<T: Display>(grid: &[Vec<T>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,10 @@
+pub mod checked;
+pub mod validate;
+
+mod args;
+mod budget;
+mod day;
+mod params;
+mod repl;
+
+pub use day::{render_grid, Context, Day};
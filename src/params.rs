@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A tunable constant of a day, declared with its default value.
+pub(crate) struct Param {
+    pub key: &'static str,
+    pub default: String,
+    check: fn(&str) -> Result<(), String>,
+}
+
+impl Param {
+    pub fn new<T>(key: &'static str, default: T) -> Self
+    where
+        T: FromStr + Display,
+        T::Err: Display,
+    {
+        Self {
+            key,
+            default: default.to_string(),
+            check: |value| value.parse::<T>().map(|_| ()).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Values of the declared parameters, with the overrides applied.
+pub(crate) struct Params {
+    values: BTreeMap<&'static str, String>,
+}
+
+impl Params {
+    pub fn new(declared: &[Param], overrides: &[(String, String)]) -> Result<Self, String> {
+        let mut params = Self {
+            values: declared.iter()
+                .map(|param| (param.key, param.default.clone()))
+                .collect(),
+        };
+
+        for (key, value) in overrides {
+            params.set(declared, key, value)?;
+        }
+
+        Ok(params)
+    }
+
+    pub fn set(&mut self, declared: &[Param], key: &str, value: &str) -> Result<(), String> {
+        let Some(param) = declared.iter().find(|param| param.key == key) else {
+            let known = declared.iter().map(|param| param.key).collect::<Vec<_>>();
+            return Err(if known.is_empty() {
+                format!("unknown parameter `{}`, this day has no parameters", key)
+            } else {
+                format!("unknown parameter `{}`, expected one of: {}", key, known.join(", "))
+            });
+        };
+
+        (param.check)(value)
+            .map_err(|e| format!("invalid value `{}` for parameter `{}`: {}", value, key, e))?;
+
+        self.values.insert(param.key, value.to_string());
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(&'static str, &str)> {
+        self.values.iter().map(|(&key, value)| (key, value.as_str()))
+    }
+
+    pub fn get<T>(&self, key: &str) -> T
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.values.get(key)
+            .unwrap_or_else(|| panic!("parameter `{}` was never declared", key));
+
+        value.parse()
+            .unwrap_or_else(|e| panic!("invalid value `{}` for parameter `{}`: {}", value, key, e))
+    }
+}
@@ -0,0 +1,77 @@
+use std::io::{self, BufRead, Write};
+use crate::day::{guarded, Context, Day};
+
+const BUILTINS: [&str; 6] = [
+    "help: list the commands",
+    "solve: print the answer of every part",
+    "compare: run every implementation of every part and time them",
+    "params: list the parameters",
+    "set <key>=<value>: change a parameter",
+    "quit: leave the REPL",
+];
+
+/// Reads commands from stdin and runs them against the already parsed puzzle.
+pub(crate) fn repl<P>(day: &Day<P>, puzzle: &P, context: &mut Context) {
+    let mut lines = io::stdin().lock().lines();
+
+    loop {
+        print!("{}> ", day.name);
+        io::stdout().flush().unwrap();
+
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            return;
+        };
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let Some((&name, args)) = words.split_first() else {
+            continue;
+        };
+
+        match name {
+            "help" => println!("{}", help(day)),
+            "solve" => {
+                day.solve(puzzle, context);
+            }
+            "compare" => {
+                day.compare(puzzle, context);
+            }
+            "params" => {
+                for (key, value) in context.params.iter() {
+                    println!("{} = {}", key, value);
+                }
+            }
+            "set" => {
+                let result = match args {
+                    [param] => match param.split_once('=') {
+                        Some((key, value)) => context.params.set(&day.params, key, value),
+                        None => Err("usage: set <key>=<value>".to_string()),
+                    },
+                    _ => Err("usage: set <key>=<value>".to_string()),
+                };
+                if let Err(e) = result {
+                    println!("{}", e);
+                }
+            }
+            "quit" | "exit" => return,
+            _ => match day.commands.iter().find(|command| command.name == name) {
+                Some(command) => {
+                    let context = &*context;
+                    match context.budget.run(|| guarded(|| (command.execute)(puzzle, context, args))) {
+                        Ok(Ok(output)) => println!("{}", output),
+                        Ok(Err(e)) => println!("{}", e),
+                        Err(failure) => println!("{}", failure),
+                    }
+                }
+                None => println!("unknown command `{}`, try `help`", name),
+            },
+        }
+    }
+}
+
+fn help<P>(day: &Day<P>) -> String {
+    BUILTINS.into_iter()
+        .chain(day.commands.iter().map(|command| command.usage.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
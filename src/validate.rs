@@ -0,0 +1,115 @@
+//! Checks of the raw input against the structure a day expects.
+//!
+//! A day declares its validator in [`Day::new`](crate::Day::new), built from these checks. Each of them returns every problem it finds rather than stopping at the first one, so
+//! that a malformed input is reported in one go instead of panicking inside a solver.
+
+use std::fmt::Display;
+
+/// A single line of digits, without a trailing newline.
+pub fn digits(input: &str) -> Vec<String> {
+    if input.is_empty() {
+        return vec!["the input is empty, expected a line of digits".to_string()];
+    }
+
+    let mut problems = Vec::new();
+    for (y, line) in input.split('\n').enumerate() {
+        if y > 0 {
+            problems.push(format!("line {}: unexpected newline, expected a single line of digits", y));
+        }
+        for (x, c) in line.chars().enumerate() {
+            if !c.is_ascii_digit() {
+                problems.push(format!("line {}, column {}: `{}` is not a digit", y + 1, x + 1, c.escape_debug()));
+            }
+        }
+    }
+
+    problems
+}
+
+/// Non-empty lines, all as wide as the first one.
+pub fn grid(input: &str) -> Vec<String> {
+    let Some(width) = input.lines().next().map(|line| line.chars().count()) else {
+        return vec!["the input is empty, expected a grid".to_string()];
+    };
+
+    input.lines()
+        .enumerate()
+        .map(|(y, line)| (y, line.chars().count()))
+        .filter(|&(_, length)| length != width)
+        .map(|(y, length)| format!("line {}: {} cells wide, expected {} like the first line", y + 1, length, width))
+        .collect()
+}
+
+/// Every cell of the grid accepted by `cell`, `expected` describing them in the problems, like
+/// `a digit`.
+pub fn cells(input: &str, expected: &str, cell: impl Fn(char) -> bool) -> Vec<String> {
+    input.lines()
+        .enumerate()
+        .flat_map(|(y, line)| line.chars()
+            .enumerate()
+            .filter(|&(_, c)| !cell(c))
+            .map(move |(x, c)| format!("line {}, column {}: `{}` is not {}", y + 1, x + 1, c.escape_debug(), expected)))
+        .collect()
+}
+
+/// Exactly one `cell` in the grid.
+pub fn exactly_one(input: &str, cell: char) -> Vec<String> {
+    let positions = input.lines()
+        .enumerate()
+        .flat_map(|(y, line)| line.chars()
+            .enumerate()
+            .filter(|&(_, c)| c == cell)
+            .map(move |(x, _)| format!("{},{}", x, y)))
+        .collect::<Vec<_>>();
+
+    match positions.len() {
+        0 => vec![format!("no `{}` found, expected exactly one", cell)],
+        1 => Vec::new(),
+        n => vec![format!("{} `{}` found at {}, expected exactly one", n, cell, positions.join(" "))],
+    }
+}
+
+/// Exactly `expected` blank lines, separating the sections of the input.
+///
+/// The days split their sections at `\n\n`, so a blank line must be empty: one holding spaces or
+/// a `\r` is reported rather than counted, as is one before the first section.
+pub fn blank_lines(input: &str, expected: usize) -> Vec<String> {
+    let lines = input.split('\n').collect::<Vec<_>>();
+    // A trailing newline leaves an empty last piece, which is not a line.
+    let lines = lines.strip_suffix(&[""]).unwrap_or(&lines);
+
+    let mut problems = lines.iter()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && line.trim().is_empty())
+        .map(|(y, line)| format!("line {}: `{}` on a blank line, which must be empty", y + 1, line.escape_debug()))
+        .collect::<Vec<_>>();
+    if lines.first().is_some_and(|line| line.is_empty()) {
+        problems.push("line 1: blank, expected the first section".to_string());
+    }
+
+    let blank = lines.iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, line)| line.is_empty())
+        .map(|(y, _)| (y + 1).to_string())
+        .collect::<Vec<_>>();
+
+    if blank.len() != expected {
+        problems.push(if blank.is_empty() {
+            format!("no blank line found, expected {}", expected)
+        } else {
+            format!("{} blank lines found at lines {}, expected {}", blank.len(), blank.join(", "), expected)
+        });
+    }
+
+    problems
+}
+
+/// Runs `check` on every line of a section starting at line `first_line`, which is only used to
+/// number the problems.
+pub fn lines<E: Display>(section: &str, first_line: usize, check: impl Fn(&str) -> Result<(), E>) -> Vec<String> {
+    section.lines()
+        .enumerate()
+        .filter_map(|(y, line)| check(line).err().map(|e| format!("line {}: {}", first_line + y, e)))
+        .collect()
+}